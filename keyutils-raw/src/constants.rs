@@ -24,7 +24,7 @@
 // (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::{KeyPermissions, KeyringSerial};
+use crate::{KeyPermissions, KeySpec};
 
 // TODO: change these to &CStr when const fns get unblocked.
 pub const KEY_TYPE_KEYRING:                 &str = "keyring";
@@ -32,13 +32,36 @@ pub const KEY_TYPE_USER:                    &str = "user";
 pub const KEY_TYPE_LOGON:                   &str = "logon";
 pub const KEY_TYPE_BIG_KEY:                 &str = "big_key";
 
-pub const KEY_SPEC_THREAD_KEYRING:          KeyringSerial = unsafe { KeyringSerial::new_unchecked(-1) };
-pub const KEY_SPEC_PROCESS_KEYRING:         KeyringSerial = unsafe { KeyringSerial::new_unchecked(-2) };
-pub const KEY_SPEC_SESSION_KEYRING:         KeyringSerial = unsafe { KeyringSerial::new_unchecked(-3) };
-pub const KEY_SPEC_USER_KEYRING:            KeyringSerial = unsafe { KeyringSerial::new_unchecked(-4) };
-pub const KEY_SPEC_USER_SESSION_KEYRING:    KeyringSerial = unsafe { KeyringSerial::new_unchecked(-5) };
-pub const KEY_SPEC_GROUP_KEYRING:           KeyringSerial = unsafe { KeyringSerial::new_unchecked(-6) };
-pub const KEY_SPEC_REQKEY_AUTH_KEY:         KeyringSerial = unsafe { KeyringSerial::new_unchecked(-7) };
+pub const KEY_TYPE_NAME_MAX:                usize = 32;                 /* Includes the NUL terminator */
+pub const KEY_MAX_DESC_SIZE:                usize = 4096;               /* Includes the NUL terminator */
+pub const KEY_MAX_PAYLOAD_SIZE:             usize = 1024 * 1024 - 1;
+
+pub const KEY_SPEC_THREAD_KEYRING:          KeySpec = KeySpec::ThreadKeyring;
+pub const KEY_SPEC_PROCESS_KEYRING:         KeySpec = KeySpec::ProcessKeyring;
+pub const KEY_SPEC_SESSION_KEYRING:         KeySpec = KeySpec::SessionKeyring;
+pub const KEY_SPEC_USER_KEYRING:            KeySpec = KeySpec::UserKeyring;
+pub const KEY_SPEC_USER_SESSION_KEYRING:    KeySpec = KeySpec::UserSessionKeyring;
+pub const KEY_SPEC_GROUP_KEYRING:           KeySpec = KeySpec::GroupKeyring;
+pub const KEY_SPEC_REQKEY_AUTH_KEY:         KeySpec = KeySpec::RequestKeyAuthKey;
+pub const KEY_SPEC_REQUESTOR_KEYRING:       KeySpec = KeySpec::RequestorKeyring;
+
+pub const KEYCTL_MOVE:                      libc::c_int = 30;
+pub const KEYCTL_CAPABILITIES:              libc::c_int = 31;
+pub const KEYCTL_WATCH_KEY:                 libc::c_int = 32;
+
+pub const KEYCTL_MOVE_EXCL:                 u32 = 0x01;
+
+pub const KEYCTL_CAPS0_CAPABILITIES:        u8 = 0x01;  /* KEYCTL_CAPABILITIES supported */
+pub const KEYCTL_CAPS0_PERSISTENT_KEYRINGS: u8 = 0x02;  /* Persistent keyrings enabled */
+pub const KEYCTL_CAPS0_DIFFIE_HELLMAN:      u8 = 0x04;  /* Diffie-Hellman computation enabled */
+pub const KEYCTL_CAPS0_PUBLIC_KEY:          u8 = 0x08;  /* Public key ops enabled */
+pub const KEYCTL_CAPS0_BIG_KEY:             u8 = 0x10;  /* big_key-type enabled */
+pub const KEYCTL_CAPS0_INVALIDATE:          u8 = 0x20;  /* KEYCTL_INVALIDATE supported */
+pub const KEYCTL_CAPS0_RESTRICT_KEYRING:    u8 = 0x40;  /* KEYCTL_RESTRICT_KEYRING supported */
+pub const KEYCTL_CAPS0_MOVE:                u8 = 0x80;  /* KEYCTL_MOVE supported */
+pub const KEYCTL_CAPS1_NS_KEYRING_NAME:     u8 = 0x01;  /* Keyring names are per-user_namespace */
+pub const KEYCTL_CAPS1_NS_KEY_TAG:          u8 = 0x02;  /* Key indexing can include a namespace tag */
+pub const KEYCTL_CAPS1_NOTIFICATIONS:       u8 = 0x04;  /* Keys generate watchable notifications */
 
 pub const KEYCTL_SUPPORTS_ENCRYPT:          u32 = 0x01;
 pub const KEYCTL_SUPPORTS_DECRYPT:          u32 = 0x02;
@@ -76,3 +99,27 @@ pub const KEY_OTH_SEARCH:  KeyPermissions = 0x0000_0008;
 pub const KEY_OTH_LINK:    KeyPermissions = 0x0000_0010;
 pub const KEY_OTH_SETATTR: KeyPermissions = 0x0000_0020;
 pub const KEY_OTH_ALL:     KeyPermissions = 0x0000_003f;
+
+pub const O_NOTIFICATION_PIPE:              libc::c_int = libc::O_EXCL;
+
+pub const IOC_WATCH_QUEUE_SET_SIZE:         libc::c_ulong = 0x5760;     /* _IO('W', 0x60) */
+pub const IOC_WATCH_QUEUE_SET_FILTER:       libc::c_ulong = 0x5761;     /* _IO('W', 0x61) */
+
+pub const WATCH_TYPE_META:                  u32 = 0;
+pub const WATCH_TYPE_KEY_NOTIFY:            u32 = 1;
+
+pub const WATCH_META_REMOVAL_NOTIFICATION:  u32 = 0;    /* watched object was removed */
+pub const WATCH_META_LOSS_NOTIFICATION:     u32 = 1;    /* data loss occurred */
+
+pub const WATCH_INFO_LENGTH:                u32 = 0x0000_007f;  /* length of record in bytes */
+pub const WATCH_INFO_ID:                    u32 = 0x0000_ff00;  /* ID of watchpoint */
+pub const WATCH_INFO_ID__SHIFT:             u32 = 8;
+
+pub const NOTIFY_KEY_INSTANTIATED:          u32 = 0;    /* key was instantiated (aux is error code) */
+pub const NOTIFY_KEY_UPDATED:               u32 = 1;    /* key was updated */
+pub const NOTIFY_KEY_LINKED:                u32 = 2;    /* key (aux) was added to watched keyring */
+pub const NOTIFY_KEY_UNLINKED:              u32 = 3;    /* key (aux) was removed from watched keyring */
+pub const NOTIFY_KEY_CLEARED:               u32 = 4;    /* keyring was cleared */
+pub const NOTIFY_KEY_REVOKED:               u32 = 5;    /* key was revoked */
+pub const NOTIFY_KEY_INVALIDATED:           u32 = 6;    /* key was invalidated */
+pub const NOTIFY_KEY_SETATTR:               u32 = 7;    /* key's attributes got changed */
@@ -31,7 +31,10 @@ use std::ptr;
 use log::error;
 use uninit::out_ref::Out;
 
-use crate::{DefaultKeyring, KeyPermissions, KeyringSerial, TimeoutSeconds};
+use crate::{
+    DefaultKeyring, KeyPermissions, KeySpec, KeyringSerial, TimeoutSeconds, KEYCTL_CAPABILITIES,
+    KEYCTL_MOVE, KEYCTL_WATCH_KEY, KEY_MAX_DESC_SIZE, KEY_MAX_PAYLOAD_SIZE, KEY_TYPE_NAME_MAX,
+};
 
 /// Reexport of `Errno` as `Error`.
 type Error = errno::Errno;
@@ -55,30 +58,83 @@ static ZERO_KEY_ID_FOUND: &str = concat!(
      report a bug.\n\n",
     env!("CARGO_PKG_REPOSITORY"),
 );
+static NEGATIVE_KEY_ID_FOUND: &str = concat!(
+    "It appears as though a negative key ID was returned. These are reserved for special keyrings \
+     and should not happen. Please report a bug.\n\n",
+    env!("CARGO_PKG_REPOSITORY"),
+);
 static BUFFER_OVERFLOW: &str = concat!(
     "The kernel returned a size that could not be represented as a `usize`. This should not be \
      possible. Please report a bug.\n\n",
     env!("CARGO_PKG_REPOSITORY"),
 );
 
-fn cstring(s: &str) -> CString {
-    CString::new(s.as_bytes()).unwrap()
+fn cstring(s: &str) -> Result<CString> {
+    // Strings with embedded NUL bytes would be silently truncated by the kernel.
+    CString::new(s.as_bytes()).map_err(|_| errno::Errno(libc::EINVAL))
+}
+
+fn opt_cstring(opt: Option<&str>) -> Result<Option<CString>> {
+    opt.map(cstring).transpose()
+}
+
+fn type_cstring(type_: &str) -> Result<CString> {
+    if type_.is_empty() || type_.len() >= KEY_TYPE_NAME_MAX {
+        return Err(errno::Errno(libc::EINVAL));
+    }
+    cstring(type_)
+}
+
+fn desc_cstring(description: &str) -> Result<CString> {
+    if description.len() >= KEY_MAX_DESC_SIZE {
+        return Err(errno::Errno(libc::EINVAL));
+    }
+    cstring(description)
+}
+
+fn opt_desc_cstring(opt: Option<&str>) -> Result<Option<CString>> {
+    opt.map(desc_cstring).transpose()
+}
+
+fn check_payload(payload: &[u8]) -> Result<()> {
+    if payload.len() > KEY_MAX_PAYLOAD_SIZE {
+        return Err(errno::Errno(libc::EINVAL));
+    }
+    Ok(())
 }
 
-fn opt_cstring(opt: Option<&str>) -> Option<CString> {
-    opt.map(cstring)
+fn check_update_payload(payload: &[u8]) -> Result<()> {
+    // The kernel only accepts a single page of data for updates.
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if page_size > 0 && payload.len() > page_size as usize {
+        return Err(errno::Errno(libc::EINVAL));
+    }
+    Ok(())
 }
 
 fn opt_cstring_ptr(opt: &Option<CString>) -> *const libc::c_char {
     opt.as_ref().map_or(ptr::null(), |cs| cs.as_ptr())
 }
 
-fn opt_key_serial(opt: Option<KeyringSerial>) -> i32 {
-    opt.map(KeyringSerial::get).unwrap_or(0)
+fn key_spec(id: KeySpec) -> Result<i32> {
+    // Negative IDs are reserved for the special IDs, so a negative serial would be silently
+    // interpreted as one of them.
+    if let KeySpec::Serial(serial) = id {
+        if serial.get() < 0 {
+            return Err(errno::Errno(libc::EINVAL));
+        }
+    }
+    Ok(id.get())
+}
+
+fn opt_key_spec(opt: Option<KeySpec>) -> Result<i32> {
+    opt.map_or(Ok(0), key_spec)
 }
 
 fn keyring_serial(res: libc::c_long) -> KeyringSerial {
-    KeyringSerial::new(res.try_into().expect(THE_KERNEL_LIED)).expect(ZERO_KEY_ID_FOUND)
+    let serial: i32 = res.try_into().expect(THE_KERNEL_LIED);
+    assert!(serial >= 0, "{}", NEGATIVE_KEY_ID_FOUND);
+    KeyringSerial::new(serial).expect(ZERO_KEY_ID_FOUND)
 }
 
 fn default_keyring(res: libc::c_long) -> Result<DefaultKeyring> {
@@ -126,10 +182,11 @@ pub fn add_key(
     type_: &str,
     description: &str,
     payload: &[u8],
-    keyring: KeyringSerial,
+    keyring: KeySpec,
 ) -> Result<KeyringSerial> {
-    let type_cstr = cstring(type_);
-    let desc_cstr = cstring(description);
+    let type_cstr = type_cstring(type_)?;
+    let desc_cstr = desc_cstring(description)?;
+    check_payload(payload)?;
     unsafe {
         syscall!(
             libc::SYS_add_key,
@@ -137,7 +194,7 @@ pub fn add_key(
             desc_cstr.as_ptr(),
             payload.as_ptr() as *const libc::c_void,
             payload.len(),
-            keyring.get(),
+            key_spec(keyring)?,
         )
     }
     .map(keyring_serial)
@@ -147,11 +204,11 @@ pub fn request_key(
     type_: &str,
     description: &str,
     callout_info: Option<&str>,
-    keyring: Option<KeyringSerial>,
+    keyring: Option<KeySpec>,
 ) -> Result<KeyringSerial> {
-    let type_cstr = cstring(type_);
-    let desc_cstr = cstring(description);
-    let callout_cstr = opt_cstring(callout_info);
+    let type_cstr = type_cstring(type_)?;
+    let desc_cstr = desc_cstring(description)?;
+    let callout_cstr = opt_cstring(callout_info)?;
     let callout_ptr = opt_cstring_ptr(&callout_cstr);
 
     unsafe {
@@ -160,17 +217,17 @@ pub fn request_key(
             type_cstr.as_ptr(),
             desc_cstr.as_ptr(),
             callout_ptr,
-            opt_key_serial(keyring),
+            opt_key_spec(keyring)?,
         )
     }
     .map(keyring_serial)
 }
 
-pub fn keyctl_get_keyring_id(id: KeyringSerial, create: bool) -> Result<KeyringSerial> {
+pub fn keyctl_get_keyring_id(id: KeySpec, create: bool) -> Result<KeyringSerial> {
     unsafe {
         keyctl!(
             libc::KEYCTL_GET_KEYRING_ID,
-            id.get(),
+            key_spec(id)?,
             if create { 1 } else { 0 },
         )
     }
@@ -178,17 +235,18 @@ pub fn keyctl_get_keyring_id(id: KeyringSerial, create: bool) -> Result<KeyringS
 }
 
 pub fn keyctl_join_session_keyring(name: Option<&str>) -> Result<KeyringSerial> {
-    let name_cstr = opt_cstring(name);
+    let name_cstr = opt_desc_cstring(name)?;
     let name_ptr = opt_cstring_ptr(&name_cstr);
 
     unsafe { keyctl!(libc::KEYCTL_JOIN_SESSION_KEYRING, name_ptr,) }.map(keyring_serial)
 }
 
-pub fn keyctl_update(id: KeyringSerial, payload: &[u8]) -> Result<()> {
+pub fn keyctl_update(id: KeySpec, payload: &[u8]) -> Result<()> {
+    check_update_payload(payload)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_UPDATE,
-            id.get(),
+            key_spec(id)?,
             payload.as_ptr() as *const libc::c_void,
             payload.len(),
         )
@@ -196,19 +254,15 @@ pub fn keyctl_update(id: KeyringSerial, payload: &[u8]) -> Result<()> {
     .map(ignore)
 }
 
-pub fn keyctl_revoke(id: KeyringSerial) -> Result<()> {
-    unsafe { keyctl!(libc::KEYCTL_REVOKE, id.get(),) }.map(ignore)
+pub fn keyctl_revoke(id: KeySpec) -> Result<()> {
+    unsafe { keyctl!(libc::KEYCTL_REVOKE, key_spec(id)?,) }.map(ignore)
 }
 
-pub fn keyctl_chown(
-    id: KeyringSerial,
-    uid: Option<libc::uid_t>,
-    gid: Option<libc::gid_t>,
-) -> Result<()> {
+pub fn keyctl_chown(id: KeySpec, uid: Option<libc::uid_t>, gid: Option<libc::gid_t>) -> Result<()> {
     unsafe {
         keyctl!(
             libc::KEYCTL_CHOWN,
-            id.get(),
+            key_spec(id)?,
             uid.unwrap_or(!0),
             gid.unwrap_or(!0),
         )
@@ -216,16 +270,16 @@ pub fn keyctl_chown(
     .map(ignore)
 }
 
-pub fn keyctl_setperm(id: KeyringSerial, perm: KeyPermissions) -> Result<()> {
-    unsafe { keyctl!(libc::KEYCTL_SETPERM, id.get(), perm,) }.map(ignore)
+pub fn keyctl_setperm(id: KeySpec, perm: KeyPermissions) -> Result<()> {
+    unsafe { keyctl!(libc::KEYCTL_SETPERM, key_spec(id)?, perm,) }.map(ignore)
 }
 
-pub fn keyctl_describe(id: KeyringSerial, mut buffer: Option<Out<[u8]>>) -> Result<usize> {
+pub fn keyctl_describe(id: KeySpec, mut buffer: Option<Out<[u8]>>) -> Result<usize> {
     let capacity = buffer.as_mut().map_or(0, |b| b.len());
     unsafe {
         keyctl!(
             libc::KEYCTL_DESCRIBE,
-            id.get(),
+            key_spec(id)?,
             buffer.as_mut().map_or(ptr::null(), |b| b.as_mut_ptr()),
             capacity,
         )
@@ -233,44 +287,62 @@ pub fn keyctl_describe(id: KeyringSerial, mut buffer: Option<Out<[u8]>>) -> Resu
     .map(size)
 }
 
-pub fn keyctl_clear(id: KeyringSerial) -> Result<()> {
-    unsafe { keyctl!(libc::KEYCTL_CLEAR, id.get(),) }.map(ignore)
+pub fn keyctl_clear(id: KeySpec) -> Result<()> {
+    unsafe { keyctl!(libc::KEYCTL_CLEAR, key_spec(id)?,) }.map(ignore)
 }
 
-pub fn keyctl_link(id: KeyringSerial, ringid: KeyringSerial) -> Result<()> {
-    unsafe { keyctl!(libc::KEYCTL_LINK, id.get(), ringid.get(),) }.map(ignore)
+pub fn keyctl_link(id: KeySpec, ringid: KeySpec) -> Result<()> {
+    unsafe { keyctl!(libc::KEYCTL_LINK, key_spec(id)?, key_spec(ringid)?,) }.map(ignore)
 }
 
-pub fn keyctl_unlink(id: KeyringSerial, ringid: KeyringSerial) -> Result<()> {
-    unsafe { keyctl!(libc::KEYCTL_UNLINK, id.get(), ringid.get(),) }.map(ignore)
+pub fn keyctl_unlink(id: KeySpec, ringid: KeySpec) -> Result<()> {
+    unsafe { keyctl!(libc::KEYCTL_UNLINK, key_spec(id)?, key_spec(ringid)?,) }.map(ignore)
+}
+
+pub fn keyctl_move(
+    id: KeySpec,
+    from_ringid: KeySpec,
+    to_ringid: KeySpec,
+    flags: u32,
+) -> Result<()> {
+    unsafe {
+        keyctl!(
+            KEYCTL_MOVE,
+            key_spec(id)?,
+            key_spec(from_ringid)?,
+            key_spec(to_ringid)?,
+            flags,
+        )
+    }
+    .map(ignore)
 }
 
 pub fn keyctl_search(
-    ringid: KeyringSerial,
+    ringid: KeySpec,
     type_: &str,
     description: &str,
-    destringid: Option<KeyringSerial>,
+    destringid: Option<KeySpec>,
 ) -> Result<KeyringSerial> {
-    let type_cstr = cstring(type_);
-    let desc_cstr = cstring(description);
+    let type_cstr = type_cstring(type_)?;
+    let desc_cstr = desc_cstring(description)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_SEARCH,
-            ringid.get(),
+            key_spec(ringid)?,
             type_cstr.as_ptr(),
             desc_cstr.as_ptr(),
-            opt_key_serial(destringid),
+            opt_key_spec(destringid)?,
         )
     }
     .map(keyring_serial)
 }
 
-pub fn keyctl_read(id: KeyringSerial, mut buffer: Option<Out<[u8]>>) -> Result<usize> {
+pub fn keyctl_read(id: KeySpec, mut buffer: Option<Out<[u8]>>) -> Result<usize> {
     let capacity = buffer.as_mut().map_or(0, |b| b.len());
     unsafe {
         keyctl!(
             libc::KEYCTL_READ,
-            id.get(),
+            key_spec(id)?,
             buffer.as_mut().map_or(ptr::null(), |b| b.as_mut_ptr()),
             capacity,
         )
@@ -278,34 +350,27 @@ pub fn keyctl_read(id: KeyringSerial, mut buffer: Option<Out<[u8]>>) -> Result<u
     .map(size)
 }
 
-pub fn keyctl_instantiate(
-    id: KeyringSerial,
-    payload: &[u8],
-    ringid: Option<KeyringSerial>,
-) -> Result<()> {
+pub fn keyctl_instantiate(id: KeySpec, payload: &[u8], ringid: Option<KeySpec>) -> Result<()> {
+    check_payload(payload)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_INSTANTIATE,
-            id.get(),
+            key_spec(id)?,
             payload.as_ptr() as *const libc::c_void,
             payload.len(),
-            opt_key_serial(ringid),
+            opt_key_spec(ringid)?,
         )
     }
     .map(ignore)
 }
 
-pub fn keyctl_negate(
-    id: KeyringSerial,
-    timeout: TimeoutSeconds,
-    ringid: Option<KeyringSerial>,
-) -> Result<()> {
+pub fn keyctl_negate(id: KeySpec, timeout: TimeoutSeconds, ringid: Option<KeySpec>) -> Result<()> {
     unsafe {
         keyctl!(
             libc::KEYCTL_NEGATE,
-            id.get(),
+            key_spec(id)?,
             timeout,
-            opt_key_serial(ringid),
+            opt_key_spec(ringid)?,
         )
     }
     .map(ignore)
@@ -315,20 +380,20 @@ pub fn keyctl_set_reqkey_keyring(reqkey_defl: DefaultKeyring) -> Result<DefaultK
     unsafe { keyctl!(libc::KEYCTL_SET_REQKEY_KEYRING, reqkey_defl,) }.and_then(default_keyring)
 }
 
-pub fn keyctl_set_timeout(key: KeyringSerial, timeout: TimeoutSeconds) -> Result<()> {
-    unsafe { keyctl!(libc::KEYCTL_SET_TIMEOUT, key.get(), timeout,) }.map(ignore)
+pub fn keyctl_set_timeout(key: KeySpec, timeout: TimeoutSeconds) -> Result<()> {
+    unsafe { keyctl!(libc::KEYCTL_SET_TIMEOUT, key_spec(key)?, timeout,) }.map(ignore)
 }
 
-pub fn keyctl_assume_authority(key: Option<KeyringSerial>) -> Result<()> {
-    unsafe { keyctl!(libc::KEYCTL_ASSUME_AUTHORITY, opt_key_serial(key),) }.map(ignore)
+pub fn keyctl_assume_authority(key: Option<KeySpec>) -> Result<()> {
+    unsafe { keyctl!(libc::KEYCTL_ASSUME_AUTHORITY, opt_key_spec(key)?,) }.map(ignore)
 }
 
-pub fn keyctl_get_security(key: KeyringSerial, mut buffer: Option<Out<[u8]>>) -> Result<usize> {
+pub fn keyctl_get_security(key: KeySpec, mut buffer: Option<Out<[u8]>>) -> Result<usize> {
     let capacity = buffer.as_mut().map_or(0, |b| b.len());
     unsafe {
         keyctl!(
             libc::KEYCTL_GET_SECURITY,
-            key.get(),
+            key_spec(key)?,
             buffer.as_mut().map_or(ptr::null(), |b| b.as_mut_ptr()),
             capacity,
         )
@@ -337,29 +402,29 @@ pub fn keyctl_get_security(key: KeyringSerial, mut buffer: Option<Out<[u8]>>) ->
 }
 
 pub fn keyctl_reject(
-    id: KeyringSerial,
+    id: KeySpec,
     timeout: TimeoutSeconds,
     error: errno::Errno,
-    ringid: Option<KeyringSerial>,
+    ringid: Option<KeySpec>,
 ) -> Result<()> {
     unsafe {
         keyctl!(
             libc::KEYCTL_REJECT,
-            id.get(),
+            key_spec(id)?,
             timeout,
             error,
-            opt_key_serial(ringid),
+            opt_key_spec(ringid)?,
         )
     }
     .map(ignore)
 }
 
-pub fn keyctl_invalidate(id: KeyringSerial) -> Result<()> {
-    unsafe { keyctl!(libc::KEYCTL_INVALIDATE, id.get(),) }.map(ignore)
+pub fn keyctl_invalidate(id: KeySpec) -> Result<()> {
+    unsafe { keyctl!(libc::KEYCTL_INVALIDATE, key_spec(id)?,) }.map(ignore)
 }
 
-pub fn keyctl_get_persistent(uid: libc::uid_t, id: KeyringSerial) -> Result<KeyringSerial> {
-    unsafe { keyctl!(libc::KEYCTL_GET_PERSISTENT, uid, id.get(),) }.map(keyring_serial)
+pub fn keyctl_get_persistent(uid: libc::uid_t, id: KeySpec) -> Result<KeyringSerial> {
+    unsafe { keyctl!(libc::KEYCTL_GET_PERSISTENT, uid, key_spec(id)?,) }.map(keyring_serial)
 }
 
 pub fn keyctl_session_to_parent() -> Result<()> {
@@ -382,15 +447,15 @@ struct DhKdfParamsKernel {
 }
 
 pub fn keyctl_dh_compute(
-    private: KeyringSerial,
-    prime: KeyringSerial,
-    base: KeyringSerial,
+    private: KeySpec,
+    prime: KeySpec,
+    base: KeySpec,
     mut buffer: Option<Out<[u8]>>,
 ) -> Result<usize> {
     let params = DhComputeParamsKernel {
-        priv_: private.get(),
-        prime: prime.get(),
-        base: base.get(),
+        priv_: key_spec(private)?,
+        prime: key_spec(prime)?,
+        base: key_spec(base)?,
     };
     let capacity = buffer.as_mut().map_or(0, |b| b.len());
     unsafe {
@@ -399,26 +464,26 @@ pub fn keyctl_dh_compute(
             &params as *const DhComputeParamsKernel,
             buffer.as_mut().map_or(ptr::null(), |b| b.as_mut_ptr()),
             capacity,
-            ptr::null() as *const DhKdfParamsKernel,
+            ptr::null::<DhKdfParamsKernel>(),
         )
     }
     .map(size)
 }
 
 pub fn keyctl_dh_compute_kdf(
-    private: KeyringSerial,
-    prime: KeyringSerial,
-    base: KeyringSerial,
+    private: KeySpec,
+    prime: KeySpec,
+    base: KeySpec,
     hashname: &str,
     otherinfo: Option<&[u8]>,
     mut buffer: Option<Out<[u8]>>,
 ) -> Result<usize> {
     let params = DhComputeParamsKernel {
-        priv_: private.get(),
-        prime: prime.get(),
-        base: base.get(),
+        priv_: key_spec(private)?,
+        prime: key_spec(prime)?,
+        base: key_spec(base)?,
     };
-    let hash_cstr = cstring(hashname);
+    let hash_cstr = cstring(hashname)?;
     let kdf_params = DhKdfParamsKernel {
         hashname: hash_cstr.as_ptr(),
         otherinfo: otherinfo.map_or(ptr::null(), |d| d.as_ptr()) as *const libc::c_void,
@@ -446,7 +511,7 @@ pub enum Restriction<'a> {
     },
 }
 
-pub fn keyctl_restrict_keyring(keyring: KeyringSerial, restriction: Restriction) -> Result<()> {
+pub fn keyctl_restrict_keyring(keyring: KeySpec, restriction: Restriction) -> Result<()> {
     let type_cstr;
     let restriction_cstr;
 
@@ -456,8 +521,8 @@ pub fn keyctl_restrict_keyring(keyring: KeyringSerial, restriction: Restriction)
             type_,
             restriction,
         } => {
-            type_cstr = cstring(type_);
-            restriction_cstr = cstring(restriction);
+            type_cstr = type_cstring(type_)?;
+            restriction_cstr = cstring(restriction)?;
 
             (type_cstr.as_ptr(), restriction_cstr.as_ptr())
         },
@@ -465,7 +530,7 @@ pub fn keyctl_restrict_keyring(keyring: KeyringSerial, restriction: Restriction)
     unsafe {
         keyctl!(
             libc::KEYCTL_RESTRICT_KEYRING,
-            keyring.get(),
+            key_spec(keyring)?,
             type_ptr,
             restriction_ptr,
         )
@@ -522,13 +587,13 @@ impl From<PKeyQueryKernel> for PKeyQuery {
     }
 }
 
-pub fn keyctl_pkey_query(key: KeyringSerial, info: &str) -> Result<PKeyQuery> {
+pub fn keyctl_pkey_query(key: KeySpec, info: &str) -> Result<PKeyQuery> {
     let mut query = PKeyQueryKernel::zeroed();
-    let info_cstr = cstring(info);
+    let info_cstr = cstring(info)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_PKEY_QUERY,
-            key.get(),
+            key_spec(key)?,
             0,
             info_cstr.as_ptr(),
             &mut query as *mut PKeyQueryKernel,
@@ -548,18 +613,18 @@ struct PKeyOpParamsKernel {
 }
 
 pub fn keyctl_pkey_encrypt(
-    key: KeyringSerial,
+    key: KeySpec,
     info: &str,
     data: &[u8],
     mut buffer: Out<[u8]>,
 ) -> Result<usize> {
     let params = PKeyOpParamsKernel {
-        key_id: key.get(),
+        key_id: key_spec(key)?,
         in_len: safe_len(data.len())?,
         out_len: safe_len(buffer.len())?,
         in2_len: 0,
     };
-    let info_cstr = cstring(info);
+    let info_cstr = cstring(info)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_PKEY_ENCRYPT,
@@ -573,18 +638,18 @@ pub fn keyctl_pkey_encrypt(
 }
 
 pub fn keyctl_pkey_decrypt(
-    key: KeyringSerial,
+    key: KeySpec,
     info: &str,
     data: &[u8],
     mut buffer: Out<[u8]>,
 ) -> Result<usize> {
     let params = PKeyOpParamsKernel {
-        key_id: key.get(),
+        key_id: key_spec(key)?,
         in_len: safe_len(data.len())?,
         out_len: safe_len(buffer.len())?,
         in2_len: 0,
     };
-    let info_cstr = cstring(info);
+    let info_cstr = cstring(info)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_PKEY_DECRYPT,
@@ -598,18 +663,18 @@ pub fn keyctl_pkey_decrypt(
 }
 
 pub fn keyctl_pkey_sign(
-    key: KeyringSerial,
+    key: KeySpec,
     info: &str,
     data: &[u8],
     mut buffer: Out<[u8]>,
 ) -> Result<usize> {
     let params = PKeyOpParamsKernel {
-        key_id: key.get(),
+        key_id: key_spec(key)?,
         in_len: safe_len(data.len())?,
         out_len: safe_len(buffer.len())?,
         in2_len: 0,
     };
-    let info_cstr = cstring(info);
+    let info_cstr = cstring(info)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_PKEY_SIGN,
@@ -622,14 +687,14 @@ pub fn keyctl_pkey_sign(
     .map(size)
 }
 
-pub fn keyctl_pkey_verify(key: KeyringSerial, info: &str, data: &[u8], sig: &[u8]) -> Result<bool> {
+pub fn keyctl_pkey_verify(key: KeySpec, info: &str, data: &[u8], sig: &[u8]) -> Result<bool> {
     let params = PKeyOpParamsKernel {
-        key_id: key.get(),
+        key_id: key_spec(key)?,
         in_len: safe_len(data.len())?,
         out_len: 0,
         in2_len: safe_len(sig.len())?,
     };
-    let info_cstr = cstring(info);
+    let info_cstr = cstring(info)?;
     unsafe {
         keyctl!(
             libc::KEYCTL_PKEY_VERIFY,
@@ -641,3 +706,31 @@ pub fn keyctl_pkey_verify(key: KeyringSerial, info: &str, data: &[u8], sig: &[u8
     }
     .map(|res| res == 0)
 }
+
+pub fn keyctl_capabilities(mut buffer: Option<Out<[u8]>>) -> Result<usize> {
+    let capacity = buffer.as_mut().map_or(0, |b| b.len());
+    unsafe {
+        keyctl!(
+            KEYCTL_CAPABILITIES,
+            buffer.as_mut().map_or(ptr::null(), |b| b.as_mut_ptr()),
+            capacity,
+        )
+    }
+    .map(size)
+}
+
+pub fn keyctl_watch_key(
+    id: KeySpec,
+    watch_queue_fd: libc::c_int,
+    watch_id: Option<u8>,
+) -> Result<()> {
+    unsafe {
+        keyctl!(
+            KEYCTL_WATCH_KEY,
+            key_spec(id)?,
+            watch_queue_fd,
+            watch_id.map_or(-1, libc::c_int::from),
+        )
+    }
+    .map(ignore)
+}
@@ -25,11 +25,82 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::convert::TryFrom;
+use std::fmt;
 use std::num::NonZeroI32;
 
 /// Alias for the key_serial_t kernel type, representing a keyring (or key).
+///
+/// Serials returned by the kernel are always positive. Special keyrings are referred to using
+/// `KeySpec` instead.
 pub type KeyringSerial = NonZeroI32;
 
+/// A reference to a key as passed to the kernel.
+///
+/// The kernel accepts either the serial of a key or one of a set of special IDs which are
+/// resolved relative to the calling thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+// #[non_exhaustive]
+pub enum KeySpec {
+    /// A key by its serial number.
+    ///
+    /// Serials must be positive. Functions given a negative serial fail with `EINVAL` rather than
+    /// passing it to the kernel where it would refer to one of the special IDs.
+    Serial(KeyringSerial),
+    /// The thread-specific keyring.
+    ThreadKeyring,
+    /// The process-specific keyring.
+    ProcessKeyring,
+    /// The session-specific keyring.
+    SessionKeyring,
+    /// The user-specific keyring.
+    UserKeyring,
+    /// The user session-specific keyring.
+    UserSessionKeyring,
+    /// The group-specific keyring.
+    GroupKeyring,
+    /// The authorization key of a key being instantiated by the thread.
+    RequestKeyAuthKey,
+    /// The destination keyring of the `request_key` call being serviced by the thread.
+    RequestorKeyring,
+}
+
+impl KeySpec {
+    /// The value to pass to the kernel.
+    pub fn get(self) -> i32 {
+        match self {
+            KeySpec::Serial(serial) => serial.get(),
+            KeySpec::ThreadKeyring => -1,
+            KeySpec::ProcessKeyring => -2,
+            KeySpec::SessionKeyring => -3,
+            KeySpec::UserKeyring => -4,
+            KeySpec::UserSessionKeyring => -5,
+            KeySpec::GroupKeyring => -6,
+            KeySpec::RequestKeyAuthKey => -7,
+            KeySpec::RequestorKeyring => -8,
+        }
+    }
+
+    /// The serial number referred to, if not a special ID.
+    pub fn serial(self) -> Option<KeyringSerial> {
+        match self {
+            KeySpec::Serial(serial) => Some(serial),
+            _ => None,
+        }
+    }
+}
+
+impl From<KeyringSerial> for KeySpec {
+    fn from(serial: KeyringSerial) -> Self {
+        KeySpec::Serial(serial)
+    }
+}
+
+impl fmt::Display for KeySpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
+
 /// Alias for the key_perm_t kernel type, representing a keyring's (or key's)
 /// permission bits.
 ///
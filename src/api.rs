@@ -25,79 +25,108 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::borrow::{Borrow, Cow};
-use std::convert::TryInto;
-use std::mem;
-use std::result;
-use std::str;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
 use std::time::Duration;
 
 use keyutils_raw::*;
 use log::error;
-use uninit::extension_traits::VecCapacity;
-
-use crate::constants::{KeyctlSupportFlags, Permission, SpecialKeyring};
+use uninit::extension_traits::{AsOut, VecCapacity};
+
+use crate::builder::{KeyBuilder, KeyringBuilder};
+use crate::constants::{
+    KeyctlSupportFlags, MoveFlags, Permission, SpecialKeyring, SystemKeyring, TimeoutRounding,
+};
+use crate::error::{ErrnoContext, Error, Operation, Result};
+use crate::handle::{description_impl, kernel_buffer, KeyHandle, KeyIdentity};
 use crate::keytype::*;
 use crate::keytypes;
-
-/// Reexport of `Errno` as `Error`.
-pub type Error = errno::Errno;
-/// Simpler `Result` type with the error already set.
-pub type Result<T> = result::Result<T, Error>;
+use crate::proc::{KeyInfo, KeyQuota, QuotaCheck};
+use crate::secret::{SecretBuffer, WipedPayload};
+use crate::sysctl::KeySysctl;
+use crate::walk::{self, SkippedKeyring, WalkAction, WalkEntry, WalkOrder};
 
 /// Request a key from the kernel.
-fn request_impl<K: KeyType>(
+fn request_impl(
+    type_: &str,
     description: &str,
     info: Option<&str>,
-    id: Option<KeyringSerial>,
+    id: Option<KeySpec>,
 ) -> Result<KeyringSerial> {
-    request_key(K::name(), description, info, id)
+    request_key(type_, description, info, id)
+        .op(Operation::RequestKey)
+        .map_err(|err| err.with_key_type(type_.to_owned()))
 }
 
-fn read_impl(id: KeyringSerial) -> Result<Vec<u8>> {
-    // Get the size of the description.
-    let mut sz = keyctl_read(id, None)?;
-    // Allocate this description.
-    let mut buffer = vec![0; sz];
-    loop {
-        let write_buffer = buffer.get_backing_buffer();
-        // Fetch the description.
-        sz = keyctl_read(id, Some(write_buffer))?;
+fn read_as_impl<K: ReadableKeyType>(id: KeyringSerial) -> Result<K::Contents> {
+    K::decode(read_impl(id)?).ok_or_else(|| {
+        Error::new(Operation::Read, errno::Errno(libc::EINVAL))
+            .with_serial(id)
+            .with_key_type(K::name())
+    })
+}
 
-        // If we got everything, exit.
-        if sz <= buffer.capacity() {
-            break;
-        }
+/// Check that an ID is a serial rather than one of the special IDs.
+fn check_serial(id: KeyringSerial) -> Result<()> {
+    if id.get() > 0 {
+        Ok(())
+    } else {
+        Err(Error::new(Operation::CheckSerial, errno::Errno(libc::EINVAL)).with_serial(id))
+    }
+}
 
-        // Resize for the additional capacity we need.
-        buffer.resize(sz, 0);
+/// Check a payload against the size limit of its key type.
+pub(crate) fn check_payload_size<K: KeyType>(
+    payload: &[u8],
+) -> std::result::Result<(), errno::Errno> {
+    match K::max_payload_size() {
+        Some(max) if payload.len() > max => Err(errno::Errno(libc::EINVAL)),
+        _ => Ok(()),
     }
-    buffer.truncate(sz);
+}
+
+fn read_impl(id: KeyringSerial) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    read_into_vec_impl(id, &mut buffer)?;
     Ok(buffer)
 }
 
+fn read_into_vec_impl(id: KeyringSerial, buffer: &mut Vec<u8>) -> Result<usize> {
+    kernel_buffer(buffer, |write_buffer| {
+        keyctl_read(id.into(), write_buffer).on(Operation::Read, id)
+    })
+}
+
 /// Representation of a kernel keyring.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Handles compare equal if they refer to the same serial, regardless of any pinned identity.
+#[derive(Debug, Clone)]
 pub struct Keyring {
     id: KeyringSerial,
+    identity: Option<Box<KeyIdentity>>,
+    permission_mask: Permission,
 }
 
 impl Keyring {
     /// Instantiate a keyring from an ID.
     ///
-    /// This is unsafe because no keyring is known to exist with the given ID.
+    /// This is unsafe because no keyring is known to exist with the given ID. Prefer
+    /// `from_serial_checked`.
     ///
     /// # Safety
     ///
     /// This method assumes that the given serial is a valid keyring ID at the kernel level.
     pub unsafe fn new(id: KeyringSerial) -> Self {
-        Keyring {
-            id,
-        }
+        Self::new_impl(id)
     }
 
     fn new_impl(id: KeyringSerial) -> Self {
         Keyring {
             id,
+            identity: None,
+            permission_mask: Permission::empty(),
         }
     }
 
@@ -105,6 +134,23 @@ impl Keyring {
         self.id
     }
 
+    /// Instantiate a keyring from an ID after checking that it exists and is a keyring.
+    ///
+    /// Fails with `EINVAL` if the ID is not positive and `ENOTDIR` if the ID refers to a key
+    /// rather than a keyring. Requires `view` permission on the keyring. Use `pin_identity` to
+    /// detect later reuse of the serial.
+    pub fn from_serial_checked(id: KeyringSerial) -> Result<Self> {
+        check_serial(id)?;
+        let description = description_impl(id)?;
+        if description.type_ == keytypes::Keyring::name() {
+            Ok(Self::new_impl(id))
+        } else {
+            Err(Error::new(Operation::Describe, errno::Errno(libc::ENOTDIR))
+                .with_serial(id)
+                .with_key_type(description.type_))
+        }
+    }
+
     /// Set the default keyring to use when implicit requests on the current thread.
     ///
     /// Returns the old default keyring.
@@ -114,7 +160,7 @@ impl Keyring {
     /// If the kernel returns a keyring value which the library does not understand, the conversion
     /// from the return value into a `DefaultKeyring` will panic.
     pub fn set_default(keyring: DefaultKeyring) -> Result<DefaultKeyring> {
-        keyctl_set_reqkey_keyring(keyring)
+        keyctl_set_reqkey_keyring(keyring).op(Operation::SetDefaultKeyring)
     }
 
     /// Requests a keyring with the given description by searching the thread, process, and session
@@ -132,16 +178,19 @@ impl Keyring {
         I: Into<Option<&'s str>>,
         T: Into<Option<TargetKeyring<'a>>>,
     {
-        request_impl::<keytypes::Keyring>(
+        request_impl(
+            keytypes::Keyring::name(),
             description.as_ref(),
             info.into().as_ref().copied(),
-            target.into().map(TargetKeyring::serial),
+            target.into().map(TargetKeyring::spec),
         )
         .map(Self::new_impl)
     }
 
     fn get_keyring(id: SpecialKeyring, create: bool) -> Result<Keyring> {
-        keyctl_get_keyring_id(id.serial(), create).map(Self::new_impl)
+        keyctl_get_keyring_id(id.spec(), create)
+            .on(Operation::GetKeyringId, id.spec())
+            .map(Self::new_impl)
     }
 
     /// Attach to a special keyring. Fails if the keyring does not already exist.
@@ -154,9 +203,47 @@ impl Keyring {
         Self::get_keyring(id, true)
     }
 
+    /// Attach to a system keyring.
+    ///
+    /// System keyrings are found by scanning `/proc/keys`, so the keyring must grant the `view`
+    /// permission to the caller. Fails with `ENOKEY` if the keyring does not exist or is not
+    /// visible.
+    pub fn attach_system(id: SystemKeyring) -> Result<Self> {
+        // Entries in `/proc/keys` may be forged, so each candidate is checked against the kernel.
+        // Userspace may not create keyrings with names starting with `.` and system keyrings are
+        // owned by root.
+        KeyInfo::all()?
+            .into_iter()
+            .filter(|info| info.type_ == keytypes::Keyring::name() && info.description == id.name())
+            .find_map(|info| {
+                let serial = keyctl_get_keyring_id(info.serial.into(), false).ok()?;
+                let desc = description_impl(serial).ok()?;
+                let is_system = desc.type_ == keytypes::Keyring::name()
+                    && desc.description == id.name()
+                    && desc.uid == 0;
+                if is_system {
+                    Some(Self::new_impl(serial))
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| Error::new(Operation::GetKeyringId, errno::Errno(libc::ENOKEY)))
+    }
+
+    /// List the keys in a system keyring.
+    ///
+    /// These are usually `asymmetric` keys holding X.509 certificates. The blacklist keyring also
+    /// contains `blacklist` keys for distrusted hashes. Keys in keyrings nested within the system
+    /// keyring are not included. Requires `read` permission on the keyring.
+    pub fn system_certificates(id: SystemKeyring) -> Result<Vec<Key>> {
+        Self::attach_system(id)?.read().map(|(keys, _)| keys)
+    }
+
     /// Create a new anonymous keyring and set it as the session keyring.
     pub fn join_anonymous_session() -> Result<Self> {
-        keyctl_join_session_keyring(None).map(Self::new_impl)
+        keyctl_join_session_keyring(None)
+            .op(Operation::JoinSessionKeyring)
+            .map(Self::new_impl)
     }
 
     /// Attached to a named session keyring.
@@ -167,14 +254,17 @@ impl Keyring {
     where
         N: AsRef<str>,
     {
-        keyctl_join_session_keyring(Some(name.as_ref())).map(Self::new_impl)
+        keyctl_join_session_keyring(Some(name.as_ref()))
+            .op(Operation::JoinSessionKeyring)
+            .map(Self::new_impl)
     }
 
     /// Clears the contents of the keyring.
     ///
     /// Requires `write` permission on the keyring.
     pub fn clear(&mut self) -> Result<()> {
-        keyctl_clear(self.id)
+        self.verify_identity()?;
+        keyctl_clear(self.id.into()).on(Operation::Clear, self.id)
     }
 
     /// Adds a link to `key` to the keyring.
@@ -182,14 +272,18 @@ impl Keyring {
     /// Any link to an existing key with the same description is removed. Requires `write`
     /// permission on the keyring and `link` permission on the key.
     pub fn link_key(&mut self, key: &Key) -> Result<()> {
-        keyctl_link(key.id, self.id)
+        self.verify_identity()?;
+        key.verify_identity()?;
+        keyctl_link(key.id.into(), self.id.into()).on(Operation::Link, self.id)
     }
 
     /// Removes the link to `key` from the keyring.
     ///
     /// Requires `write` permission on the keyring.
     pub fn unlink_key(&mut self, key: &Key) -> Result<()> {
-        keyctl_unlink(key.id, self.id)
+        self.verify_identity()?;
+        key.verify_identity()?;
+        keyctl_unlink(key.id.into(), self.id.into()).on(Operation::Unlink, self.id)
     }
 
     /// Adds a link to `keyring` to the keyring.
@@ -197,30 +291,84 @@ impl Keyring {
     /// Any link to an existing keyring with the same description is removed. Requires `write`
     /// permission on the current keyring and `link` permission on the linked keyring.
     pub fn link_keyring(&mut self, keyring: &Keyring) -> Result<()> {
-        keyctl_link(keyring.id, self.id)
+        self.verify_identity()?;
+        keyring.verify_identity()?;
+        keyctl_link(keyring.id.into(), self.id.into()).on(Operation::Link, self.id)
     }
 
     /// Removes the link to `keyring` from the keyring.
     ///
     /// Requires `write` permission on the keyring.
     pub fn unlink_keyring(&mut self, keyring: &Keyring) -> Result<()> {
-        keyctl_unlink(keyring.id, self.id)
+        self.verify_identity()?;
+        keyring.verify_identity()?;
+        keyctl_unlink(keyring.id.into(), self.id.into()).on(Operation::Unlink, self.id)
+    }
+
+    /// Moves the link to `key` from the keyring into `destination`.
+    ///
+    /// The key is atomically unlinked from the keyring and linked into `destination`, so it is
+    /// never unreachable or linked into both keyrings. Any link to an existing key with the same
+    /// type and description in `destination` is removed unless `MoveFlags::EXCLUSIVE` is given in
+    /// which case the move fails with `ErrorKind::AlreadyExists`. Requires `write` permission on
+    /// both keyrings and `link` permission on the key.
+    pub fn move_key(
+        &mut self,
+        key: &Key,
+        destination: &mut Keyring,
+        flags: MoveFlags,
+    ) -> Result<()> {
+        self.verify_identity()?;
+        key.verify_identity()?;
+        destination.verify_identity()?;
+        keyctl_move(
+            key.id.into(),
+            self.id.into(),
+            destination.id.into(),
+            flags.bits(),
+        )
+        .on(Operation::Move, self.id)
     }
 
-    fn search_impl<K>(
+    /// Moves the link to `keyring` from the keyring into `destination`.
+    ///
+    /// The keyring is atomically unlinked from the keyring and linked into `destination`, so it
+    /// is never unreachable or linked into both keyrings. Any link to an existing keyring with
+    /// the same description in `destination` is removed unless `MoveFlags::EXCLUSIVE` is given
+    /// in which case the move fails with `ErrorKind::AlreadyExists`. Requires `write`
+    /// permission on both keyrings and `link` permission on the moved keyring.
+    pub fn move_keyring(
+        &mut self,
+        keyring: &Keyring,
+        destination: &mut Keyring,
+        flags: MoveFlags,
+    ) -> Result<()> {
+        self.verify_identity()?;
+        keyring.verify_identity()?;
+        destination.verify_identity()?;
+        keyctl_move(
+            keyring.id.into(),
+            self.id.into(),
+            destination.id.into(),
+            flags.bits(),
+        )
+        .on(Operation::Move, self.id)
+    }
+
+    fn search_impl(
         &self,
+        type_: &str,
         description: &str,
         destination: Option<&mut Keyring>,
-    ) -> Result<KeyringSerial>
-    where
-        K: KeyType,
-    {
+    ) -> Result<KeyringSerial> {
         keyctl_search(
-            self.id,
-            K::name(),
+            self.id.into(),
+            type_,
             description,
-            destination.map(|dest| dest.id),
+            destination.map(|dest| dest.id.into()),
         )
+        .on(Operation::Search, self.id)
+        .map_err(|err| err.with_key_type(type_.to_owned()))
     }
 
     /// Recursively search the keyring for a key with the matching description.
@@ -228,13 +376,33 @@ impl Keyring {
     /// If it is found, it is attached to the keyring (if `write` permission to the keyring and
     /// `link` permission on the key exist) and return it. Requires the `search` permission on the
     /// keyring. Any children keyrings without the `search` permission are ignored.
-    pub fn search_for_key<'a, K, D, DK>(&self, description: D, destination: DK) -> Result<Key>
+    pub fn search_for_key<'a, K, D, DK>(
+        &self,
+        description: D,
+        destination: DK,
+    ) -> Result<TypedKey<K>>
     where
         K: KeyType,
         D: Borrow<K::Description>,
         DK: Into<Option<&'a mut Keyring>>,
     {
-        self.search_impl::<K>(&description.borrow().description(), destination.into())
+        self.search_for_key_dyn(K::name(), &description.borrow().description(), destination)
+            .map(TypedKey::new_impl)
+    }
+
+    /// Recursively search the keyring for a key of a type named at runtime.
+    ///
+    /// See `search_for_key`.
+    pub fn search_for_key_dyn<'a, DK>(
+        &self,
+        type_: &str,
+        description: &str,
+        destination: DK,
+    ) -> Result<Key>
+    where
+        DK: Into<Option<&'a mut Keyring>>,
+    {
+        self.search_impl(type_, description, destination.into())
             .map(Key::new_impl)
     }
 
@@ -249,109 +417,231 @@ impl Keyring {
         D: Borrow<<keytypes::Keyring as KeyType>::Description>,
         DK: Into<Option<&'a mut Keyring>>,
     {
-        self.search_impl::<keytypes::Keyring>(
+        self.search_impl(
+            keytypes::Keyring::name(),
             &description.borrow().description(),
             destination.into(),
         )
         .map(Self::new_impl)
     }
 
-    /// Return all immediate children of the keyring.
+    /// Return an iterator over the immediate children of the keyring.
     ///
-    /// Requires `read` permission on the keyring.
-    pub fn read(&self) -> Result<(Vec<Key>, Vec<Keyring>)> {
-        // The `description` check below hides this error code from the kernel.
-        if self.id.get() == 0 {
-            return Err(errno::Errno(libc::ENOKEY));
-        }
-
+    /// Only the serials of the children are read; they are classified as keys or keyrings on
+    /// demand. Requires `read` permission on the keyring.
+    pub fn children(&self) -> Result<KeyringChildren> {
         // Avoid a panic in the code below be ensuring that we actually have a keyring. Parsing
         // a key's payload as a keyring payload.
-        let desc = self.description()?;
+        let desc = match self.description() {
+            Ok(desc) => desc,
+            // The kernel reports any lookup failure from `KEYCTL_READ` as `ENOKEY`, but the
+            // `description` check hides this error code, so translate it here.
+            Err(err) if err == errno::Errno(libc::EINVAL) => {
+                return Err(
+                    Error::new(Operation::Read, errno::Errno(libc::ENOKEY)).with_serial(self.id)
+                );
+            },
+            Err(err) => return Err(err),
+        };
         if desc.type_ != keytypes::Keyring::name() {
-            return Err(errno::Errno(libc::ENOTDIR));
+            return Err(Error::new(Operation::Read, errno::Errno(libc::ENOTDIR))
+                .with_serial(self.id)
+                .with_key_type(desc.type_));
         }
 
-        let buffer = read_impl(self.id)?;
-        let keyring_children = {
-            let chunk_size = mem::size_of::<KeyringSerial>();
-            let chunks = buffer.chunks(chunk_size);
-            chunks.map(|chunk| {
-                let bytes = chunk.try_into().map_err(|err| {
-                    error!(
-                        "A keyring did not have the right number of bytes for a child key or \
-                         keyring ID: {}",
-                        err,
-                    );
-                    errno::Errno(libc::EINVAL)
-                })?;
-                let id = i32::from_ne_bytes(bytes);
-                let serial = KeyringSerial::new(id).ok_or_else(|| {
-                    error!("A keyring had a child key or keyring ID of 0");
-                    errno::Errno(libc::EINVAL)
-                })?;
-                Ok(Key::new_impl(serial))
-            })
-        };
+        Ok(KeyringChildren {
+            serials: read_as_impl::<keytypes::Keyring>(self.id)?.into_iter(),
+            keyrings: None,
+        })
+    }
+
+    /// Return all immediate children of the keyring.
+    ///
+    /// Requires `read` permission on the keyring.
+    pub fn read(&self) -> Result<(Vec<Key>, Vec<Keyring>)> {
+        let mut children = self.children()?;
+        if children.len() > BATCH_CLASSIFY_THRESHOLD {
+            // If `/proc/keys` is not available, the children are described individually.
+            let _ = children.batch_classify();
+        }
 
         let mut keys = Vec::new();
         let mut keyrings = Vec::new();
-        for key in keyring_children {
-            let key = key?;
-            match key.description() {
-                Ok(description) => {
-                    if description.type_ == keytypes::Keyring::name() {
-                        keyrings.push(Keyring::new_impl(key.id))
-                    } else {
-                        keys.push(key)
-                    }
-                },
+        for child in children {
+            match child.classify() {
+                Ok(KeyringChild::Key(key)) => keys.push(key),
+                Ok(KeyringChild::Keyring(keyring)) => keyrings.push(keyring),
                 // Keys can be invalidated between reading the keyring and
                 // reading the child key's description. If this happens, we get
                 // ENOKEY and just skip that key.
-                Err(errno::Errno(libc::ENOKEY)) => {},
+                Err(err) if err == errno::Errno(libc::ENOKEY) => {},
                 Err(e) => return Err(e),
             }
         }
         Ok((keys, keyrings))
     }
 
+    /// Recursively visit every key and keyring reachable from the keyring.
+    ///
+    /// The visitor is called for each object with the path of keyrings leading to it and decides
+    /// whether to descend into keyrings. Keys within a keyring are visited before its keyrings and
+    /// each keyring is visited once even if it is linked into multiple keyrings. Requires `read`
+    /// permission on the keyring.
+    ///
+    /// Keyrings which cannot be read are skipped and returned with the reason. Objects which
+    /// disappear during the walk are ignored.
+    pub fn walk<F>(&self, order: WalkOrder, visitor: F) -> Result<Vec<SkippedKeyring>>
+    where
+        F: FnMut(&WalkEntry) -> WalkAction,
+    {
+        walk::walk(self, order, visitor)
+    }
+
     /// Attach the persistent keyring for the current user to the current keyring.
     ///
     /// If one does not exist, it will be created. Requires `write` permission on the keyring.
+    ///
+    /// Each time the persistent keyring is attached, its expiry is reset to the
+    /// `persistent_keyring_expiry` tunable (see `KeySysctl`).
     pub fn attach_persistent(&mut self) -> Result<Self> {
-        keyctl_get_persistent(!0, self.id).map(Self::new_impl)
+        self.verify_identity()?;
+        keyctl_get_persistent(!0, self.id.into())
+            .on(Operation::GetPersistent, self.id)
+            .map(Self::new_impl)
+    }
+
+    /// Attach the persistent keyring for the current user and report its effective expiry.
+    ///
+    /// The expiry is read from the `persistent_keyring_expiry` tunable before the keyring is
+    /// attached so that a failure to read it leaves the keyring untouched. See
+    /// `attach_persistent`.
+    pub fn attach_persistent_with_expiry(&mut self) -> Result<(Self, Duration)> {
+        let expiry = KeySysctl::read_persistent_keyring_expiry()?;
+        let keyring = self.attach_persistent()?;
+        Ok((keyring, expiry))
     }
 
     /// Adds a key of a specific type to the keyring.
     ///
     /// If a key with the same description already exists and has the `update` permission, it will
     /// be updated, otherwise the link to the old key will be removed. Requires `write` permission.
-    pub fn add_key<K, D, P>(&mut self, description: D, payload: P) -> Result<Key>
+    pub fn add_key<K, D, P>(&mut self, description: D, payload: P) -> Result<TypedKey<K>>
     where
         K: KeyType,
         D: Borrow<K::Description>,
         P: Borrow<K::Payload>,
     {
-        self.add_key_impl::<K>(description.borrow(), payload.borrow())
-            .map(Key::new_impl)
+        let payload = payload.borrow().wiped_payload();
+        check_payload_size::<K>(&payload)
+            .on(Operation::AddKey, self.id)
+            .map_err(|err| err.with_key_type(K::name()))?;
+        self.add_key_dyn(K::name(), &description.borrow().description(), &payload)
+            .map(TypedKey::new_impl)
     }
 
-    /// Monomorphization of adding a key.
-    fn add_key_impl<K>(
-        &mut self,
-        description: &K::Description,
-        payload: &K::Payload,
-    ) -> Result<KeyringSerial>
+    /// Estimate whether adding a key to the keyring would fit within the current user's quota.
+    ///
+    /// The estimate is based on the description and payload of the key. Key types which store
+    /// their payload differently than it is given (e.g., `big_key`) may be charged a different
+    /// amount by the kernel.
+    pub fn check_quota<K, D, P>(&self, description: D, payload: P) -> Result<QuotaCheck>
+    where
+        K: KeyType,
+        D: Borrow<K::Description>,
+        P: Borrow<K::Payload>,
+    {
+        let quota = KeyQuota::current()?;
+        Ok(quota.check(
+            &description.borrow().description(),
+            &payload.borrow().wiped_payload(),
+        ))
+    }
+
+    /// Adds a key to the keyring after checking that it fits within the current user's quota.
+    ///
+    /// Fails with `EDQUOT` without creating the key if the key is not expected to fit. The error
+    /// carries the failed check (see `Error::quota`) to show how close the user is to their
+    /// limits. See `add_key`.
+    pub fn add_key_checked<K, D, P>(&mut self, description: D, payload: P) -> Result<TypedKey<K>>
     where
         K: KeyType,
+        D: Borrow<K::Description>,
+        P: Borrow<K::Payload>,
     {
-        add_key(
-            K::name(),
-            &description.description(),
-            &payload.payload(),
-            self.id,
-        )
+        let description = description.borrow().description();
+        let payload = payload.borrow().wiped_payload();
+        check_payload_size::<K>(&payload)
+            .on(Operation::AddKey, self.id)
+            .map_err(|err| err.with_key_type(K::name()))?;
+        let check = KeyQuota::current()?.check(&description, &payload);
+        if !check.fits() {
+            return Err(Error::new(Operation::AddKey, errno::Errno(libc::EDQUOT))
+                .with_serial(self.id)
+                .with_key_type(K::name())
+                .with_quota(check));
+        }
+        self.add_key_dyn(K::name(), &description, &payload)
+            .map(TypedKey::new_impl)
+    }
+
+    /// Start creating a key in the keyring with attributes set as soon as it exists.
+    ///
+    /// See `KeyBuilder`.
+    pub fn create<K, D, P>(&mut self, description: D, payload: P) -> KeyBuilder<'_, K>
+    where
+        K: KeyType,
+        D: Borrow<K::Description>,
+        P: Borrow<K::Payload>,
+    {
+        let description = description.borrow().description().into_owned();
+        let payload = WipedPayload::new(Cow::Owned(payload.borrow().payload().into_owned()));
+        KeyBuilder::new(self, description, payload)
+    }
+
+    /// Start creating a keyring in the keyring with attributes set as soon as it exists.
+    ///
+    /// See `KeyringBuilder`.
+    pub fn create_keyring<D>(&mut self, description: D) -> KeyringBuilder<'_>
+    where
+        D: Borrow<<keytypes::Keyring as KeyType>::Description>,
+    {
+        let description = description.borrow().description().into_owned();
+        KeyringBuilder::new(self, description)
+    }
+
+    /// The permissions removed from keys created with `create` and `create_keyring`.
+    pub fn permission_mask(&self) -> Permission {
+        self.permission_mask
+    }
+
+    /// Set the permissions to remove from keys created with `create` and `create_keyring`.
+    ///
+    /// Like a `umask`, the mask is removed from the permissions given to the builder or, if
+    /// none are given, from the default permissions of the new key. The mask belongs to this
+    /// handle (and its clones) rather than the keyring in the kernel.
+    pub fn set_permission_mask(&mut self, mask: Permission) {
+        self.permission_mask = mask;
+    }
+
+    /// Adds a key of a type named at runtime to the keyring.
+    ///
+    /// This supports key types which are not known to this crate. No validation of the
+    /// description or payload is done beyond the limits imposed on all keys. See `add_key`.
+    pub fn add_key_dyn(&mut self, type_: &str, description: &str, payload: &[u8]) -> Result<Key> {
+        self.add_key_impl(type_, description, payload)
+            .map(Key::new_impl)
+    }
+
+    fn add_key_impl(
+        &mut self,
+        type_: &str,
+        description: &str,
+        payload: &[u8],
+    ) -> Result<KeyringSerial> {
+        self.verify_identity()?;
+        add_key(type_, description, payload, self.id.into())
+            .on(Operation::AddKey, self.id)
+            .map_err(|err| err.with_key_type(type_.to_owned()))
     }
 
     /// Adds a keyring to the current keyring.
@@ -362,44 +652,77 @@ impl Keyring {
     where
         D: Borrow<<keytypes::Keyring as KeyType>::Description>,
     {
-        self.add_key_impl::<keytypes::Keyring>(description.borrow(), &())
-            .map(Self::new_impl)
+        self.add_key_impl(
+            keytypes::Keyring::name(),
+            &description.borrow().description(),
+            &[],
+        )
+        .map(Self::new_impl)
     }
 
     /// Revokes the keyring.
     ///
-    /// Requires `write` permission on the keyring.
+    /// Requires `write` permission on the keyring. See `KeyHandle::revoke`.
     pub fn revoke(self) -> Result<()> {
-        keyctl_revoke(self.id)
+        KeyHandle::revoke(self)
     }
 
     /// Change the user which owns the keyring.
     ///
     /// Requires the `setattr` permission on the keyring and the SysAdmin capability to change it
-    /// to anything other than the current user.
+    /// to anything other than the current user. See `KeyHandle::chown`.
     pub fn chown(&mut self, uid: libc::uid_t) -> Result<()> {
-        keyctl_chown(self.id, Some(uid), None)
+        KeyHandle::chown(self, uid)
     }
 
     /// Change the group which owns the keyring.
     ///
     /// Requires the `setattr` permission on the keyring and the SysAdmin capability to change it
-    /// to anything other than a group of which the current user is a member.
+    /// to anything other than a group of which the current user is a member. See
+    /// `KeyHandle::chgrp`.
     pub fn chgrp(&mut self, gid: libc::gid_t) -> Result<()> {
-        keyctl_chown(self.id, None, Some(gid))
+        KeyHandle::chgrp(self, gid)
     }
 
     /// Set the permissions on the keyring.
     ///
     /// Requires the `setattr` permission on the keyring and the SysAdmin capability if the current
-    /// user does not own the keyring.
+    /// user does not own the keyring. See `KeyHandle::set_permissions`.
     pub fn set_permissions(&mut self, perms: Permission) -> Result<()> {
-        keyctl_setperm(self.id, perms.bits())
+        KeyHandle::set_permissions(self, perms)
     }
 
     #[cfg(test)]
     pub(crate) fn set_permissions_raw(&mut self, perms: KeyPermissions) -> Result<()> {
-        keyctl_setperm(self.id, perms)
+        keyctl_setperm(self.id.into(), perms).on(Operation::SetPermissions, self.id)
+    }
+
+    /// Retrieve metadata about the keyring.
+    ///
+    /// Malformed data from the kernel is reported as an `EINVAL` error. See
+    /// `KeyHandle::description`.
+    pub fn description(&self) -> Result<Description> {
+        KeyHandle::description(self)
+    }
+
+    /// Set an expiration timer on the keyring to `timeout`.
+    ///
+    /// Any partial seconds are ignored. A timeout of 0 means "no expiration". Requires the
+    /// `setattr` permission on the keyring. See `KeyHandle::set_timeout`.
+    pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        KeyHandle::set_timeout(self, timeout)
+    }
+
+    /// The security context of the keyring. Depends on the security manager loaded into the kernel
+    /// (e.g., SELinux or AppArmor). See `KeyHandle::security`.
+    pub fn security(&self) -> Result<String> {
+        KeyHandle::security(self)
+    }
+
+    /// Invalidates the keyring and schedules it for removal. Requires the `search` permission on
+    /// the keyring. See `KeyHandle::invalidate`.
+    pub fn invalidate(self) -> Result<()> {
+        KeyHandle::invalidate(self)
     }
 
     /// Restrict all links into the keyring.
@@ -407,7 +730,9 @@ impl Keyring {
     /// Requires the `setattr` permission on the keyring and the SysAdmin capability to change it to
     /// anything other than the current user.
     pub fn restrict_all(&mut self) -> Result<()> {
-        keyctl_restrict_keyring(self.id, Restriction::AllLinks)
+        self.verify_identity()?;
+        keyctl_restrict_keyring(self.id.into(), Restriction::AllLinks)
+            .on(Operation::RestrictKeyring, self.id)
     }
 
     /// Restrict links into the keyring.
@@ -419,98 +744,180 @@ impl Keyring {
         K: RestrictableKeyType,
         R: Borrow<K::Restriction>,
     {
+        self.verify_identity()?;
         keyctl_restrict_keyring(
-            self.id,
+            self.id.into(),
             Restriction::ByType {
                 type_: K::name(),
                 restriction: &restriction.borrow().restriction(),
             },
         )
+        .on(Operation::RestrictKeyring, self.id)
+        .map_err(|err| err.with_key_type(K::name()))
     }
+}
 
-    fn description_raw(&self) -> Result<String> {
-        // Get the size of the description.
-        let mut sz = keyctl_describe(self.id, None)?;
-        // Allocate this description.
-        let mut buffer = vec![0; sz];
-        loop {
-            let write_buffer = buffer.get_backing_buffer();
-            // Fetch the description.
-            sz = keyctl_describe(self.id, Some(write_buffer))?;
+/// The number of children above which `Keyring::read` classifies them using `/proc/keys`.
+const BATCH_CLASSIFY_THRESHOLD: usize = 16;
 
-            // If we got everything, exit.
-            if sz <= buffer.capacity() {
-                break;
-            }
+/// An immediate child of a keyring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyringChild {
+    /// A key.
+    Key(Key),
+    /// A keyring.
+    Keyring(Keyring),
+}
 
-            // Resize for the additional capacity we need.
-            buffer.resize(sz, 0);
-        }
-        // Remove 1 from the size for the trailing NUL the kernel adds.
-        buffer.truncate(sz.saturating_sub(1));
-        // The kernel guarantees that we get ASCII data from this.
-        let str_slice = str::from_utf8(&buffer[..]).unwrap();
-        Ok(str_slice.to_owned())
+/// A link to a child of a keyring which has not been classified yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyringLink {
+    serial: KeyringSerial,
+    is_keyring: Option<bool>,
+}
+
+impl KeyringLink {
+    /// The serial of the child.
+    pub fn serial(&self) -> KeyringSerial {
+        self.serial
     }
 
-    /// Retrieve metadata about the keyring.
+    /// Retrieve the description of the child.
     ///
-    /// # Panics
-    ///
-    /// If the kernel returns malformed data, the the parser will panic.
+    /// Requires `view` permission on the child.
     pub fn description(&self) -> Result<Description> {
-        self.description_raw()
-            .and_then(|desc| Description::parse(&desc).ok_or(errno::Errno(libc::EINVAL)))
+        Key::new_impl(self.serial).description()
     }
 
-    /// Set an expiration timer on the keyring to `timeout`.
+    /// Determine whether the child is a key or a keyring.
     ///
-    /// Any partial seconds are ignored. A timeout of 0 means "no expiration". Requires the
-    /// `setattr` permission on the keyring.
-    pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
-        keyctl_set_timeout(self.id, timeout.as_secs() as TimeoutSeconds)
+    /// The child is described unless it was classified by `KeyringChildren::batch_classify`.
+    /// Requires `view` permission on the child in that case.
+    pub fn classify(&self) -> Result<KeyringChild> {
+        let is_keyring = if let Some(is_keyring) = self.is_keyring {
+            is_keyring
+        } else {
+            self.description()?.type_ == keytypes::Keyring::name()
+        };
+
+        Ok(if is_keyring {
+            KeyringChild::Keyring(Keyring::new_impl(self.serial))
+        } else {
+            KeyringChild::Key(Key::new_impl(self.serial))
+        })
     }
+}
 
-    /// The security context of the keyring. Depends on the security manager loaded into the kernel
-    /// (e.g., SELinux or AppArmor).
-    pub fn security(&self) -> Result<String> {
-        // Get the size of the description.
-        let mut sz = keyctl_get_security(self.id, None)?;
-        // Allocate this description.
-        let mut buffer = vec![0; sz];
-        loop {
-            let write_buffer = buffer.get_backing_buffer();
-            // Fetch the description.
-            sz = keyctl_get_security(self.id, Some(write_buffer))?;
+/// An iterator over the immediate children of a keyring.
+#[derive(Debug, Clone)]
+pub struct KeyringChildren {
+    serials: std::vec::IntoIter<KeyringSerial>,
+    keyrings: Option<HashMap<KeyringSerial, bool>>,
+}
 
-            // If we got everything, exit.
-            if sz <= buffer.capacity() {
-                break;
-            }
+impl KeyringChildren {
+    /// Classify the remaining children using a single read of `/proc/keys`.
+    ///
+    /// Children which are not visible in `/proc/keys` are still described when classified.
+    /// Since entries in `/proc/keys` may be forged, children with more than one entry are also
+    /// described instead.
+    pub fn batch_classify(&mut self) -> Result<()> {
+        let serials = self.serials.as_slice().iter().collect::<HashSet<_>>();
+        let mut entries = HashMap::new();
+        KeyInfo::all()?
+            .into_iter()
+            .filter(|info| serials.contains(&info.serial))
+            .for_each(|info| {
+                entries
+                    .entry(info.serial)
+                    // The `keyring` type name is short enough to never be truncated.
+                    .and_modify(|entry| *entry = None)
+                    .or_insert_with(|| Some(info.type_ == keytypes::Keyring::name()));
+            });
+        let keyrings = entries
+            .into_iter()
+            .filter_map(|(serial, is_keyring)| is_keyring.map(|is_keyring| (serial, is_keyring)))
+            .collect();
+        self.keyrings = Some(keyrings);
+        Ok(())
+    }
+}
 
-            // Resize for the additional capacity we need.
-            buffer.resize(sz, 0);
-        }
-        // Remove 1 from the size for the trailing NUL the kernel adds.
-        buffer.truncate(sz.saturating_sub(1));
-        // The kernel guarantees that we get ASCII data from this.
-        let str_slice = str::from_utf8(&buffer[..]).unwrap();
-        Ok(str_slice.to_owned())
+impl Iterator for KeyringChildren {
+    type Item = KeyringLink;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let serial = self.serials.next()?;
+        let is_keyring = self
+            .keyrings
+            .as_ref()
+            .and_then(|keyrings| keyrings.get(&serial).copied());
+        Some(KeyringLink {
+            serial,
+            is_keyring,
+        })
     }
 
-    /// Invalidates the keyring and schedules it for removal. Requires the `search` permission on
-    /// the keyring.
-    pub fn invalidate(self) -> Result<()> {
-        keyctl_invalidate(self.id)
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.serials.size_hint()
+    }
+}
+
+impl ExactSizeIterator for KeyringChildren {}
+
+impl KeyHandle for Keyring {
+    fn serial(&self) -> KeyringSerial {
+        self.id
+    }
+
+    fn identity(&self) -> Option<&KeyIdentity> {
+        self.identity.as_deref()
+    }
+
+    fn set_identity(&mut self, identity: Option<KeyIdentity>) {
+        self.identity = identity.map(Box::new);
     }
 }
 
+impl PartialEq for Keyring {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.id == rhs.id
+    }
+}
+
+impl Eq for Keyring {}
+
 /// Representation of a kernel key.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Handles compare equal if they refer to the same serial, regardless of any pinned identity.
+#[derive(Debug, Clone)]
 pub struct Key {
     id: KeyringSerial,
+    identity: Option<Box<KeyIdentity>>,
 }
 
+impl KeyHandle for Key {
+    fn serial(&self) -> KeyringSerial {
+        self.id
+    }
+
+    fn identity(&self) -> Option<&KeyIdentity> {
+        self.identity.as_deref()
+    }
+
+    fn set_identity(&mut self, identity: Option<KeyIdentity>) {
+        self.identity = identity.map(Box::new);
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.id == rhs.id
+    }
+}
+
+impl Eq for Key {}
+
 /// Structure to store results from a query on optional feature support for a key.
 #[derive(Debug, Clone, Copy)]
 pub struct KeySupportInfo {
@@ -664,7 +1071,8 @@ impl PublicKeyOptions {
 impl Key {
     /// Instantiate a key from an ID.
     ///
-    /// This is unsafe because no key is known to exist with the given ID.
+    /// This is unsafe because no key is known to exist with the given ID. Prefer
+    /// `from_serial_checked`.
     ///
     /// # Safety
     ///
@@ -676,6 +1084,7 @@ impl Key {
     fn new_impl(id: KeyringSerial) -> Self {
         Key {
             id,
+            identity: None,
         }
     }
 
@@ -683,6 +1092,23 @@ impl Key {
         self.id
     }
 
+    /// Instantiate a key from an ID after checking that it exists and is not a keyring.
+    ///
+    /// Fails with `EINVAL` if the ID is not positive and `EISDIR` if the ID refers to a keyring.
+    /// Requires `view` permission on the key. Use `downcast` to check for a specific key type and
+    /// `pin_identity` to detect later reuse of the serial.
+    pub fn from_serial_checked(id: KeyringSerial) -> Result<Self> {
+        check_serial(id)?;
+        let description = description_impl(id)?;
+        if description.type_ == keytypes::Keyring::name() {
+            Err(Error::new(Operation::Describe, errno::Errno(libc::EISDIR))
+                .with_serial(id)
+                .with_key_type(description.type_))
+        } else {
+            Ok(Self::new_impl(id))
+        }
+    }
+
     /// Requests a key with the given type and description by searching the thread, process, and
     /// session keyrings.
     ///
@@ -692,21 +1118,96 @@ impl Key {
     /// If `target` is given, the found keyring will be linked into it. If `target` is not given
     /// and a new key is constructed due to the request, it will be linked into the default
     /// keyring (see `Keyring::set_default`).
-    pub fn request<'s, 'a, K, D, I, T>(description: D, info: I, target: T) -> Result<Self>
+    pub fn request<'s, 'a, K, D, I, T>(description: D, info: I, target: T) -> Result<TypedKey<K>>
     where
         K: KeyType,
         D: Borrow<K::Description>,
         I: Into<Option<&'s str>>,
         T: Into<Option<TargetKeyring<'a>>>,
     {
-        request_impl::<K>(
-            &description.borrow().description(),
+        Self::request_dyn(K::name(), &description.borrow().description(), info, target)
+            .map(TypedKey::new_impl)
+    }
+
+    /// Requests a key of a type named at runtime.
+    ///
+    /// See `request`.
+    pub fn request_dyn<'s, 'a, I, T>(
+        type_: &str,
+        description: &str,
+        info: I,
+        target: T,
+    ) -> Result<Self>
+    where
+        I: Into<Option<&'s str>>,
+        T: Into<Option<TargetKeyring<'a>>>,
+    {
+        request_impl(
+            type_,
+            description,
             info.into().as_ref().copied(),
-            target.into().map(TargetKeyring::serial),
+            target.into().map(TargetKeyring::spec),
         )
         .map(Self::new_impl)
     }
 
+    /// Find a key by its type and description among all keys visible to the current process.
+    ///
+    /// Unlike `Keyring::search_for_key`, the key does not need to be in a keyring possessed by
+    /// the process; `view` permission on the key is enough. The process's keyrings are searched
+    /// first and `/proc/keys` is scanned if the key is not found there. If `destination` is given,
+    /// the key is linked into it which requires `write` permission on the keyring and `link`
+    /// permission on the key.
+    pub fn find<'a, K, D, DK>(description: D, destination: DK) -> Result<TypedKey<K>>
+    where
+        K: KeyType,
+        D: Borrow<K::Description>,
+        DK: Into<Option<&'a mut Keyring>>,
+    {
+        Self::find_dyn(K::name(), &description.borrow().description(), destination)
+            .map(TypedKey::new_impl)
+    }
+
+    /// Find a key of a type named at runtime among all keys visible to the current process.
+    ///
+    /// See `find`.
+    pub fn find_dyn<'a, DK>(type_: &str, description: &str, destination: DK) -> Result<Self>
+    where
+        DK: Into<Option<&'a mut Keyring>>,
+    {
+        let destination = destination.into();
+        let dest_id = destination.as_ref().map(|dest| dest.id.into());
+
+        // Without callout information, the kernel will not try to construct the key.
+        let request_err = match request_impl(type_, description, None, dest_id) {
+            Ok(id) => return Ok(Self::new_impl(id)),
+            Err(err) if err == errno::Errno(libc::ENOMEM) => return Err(err),
+            Err(err) => err,
+        };
+
+        let found = KeyInfo::all()?
+            .into_iter()
+            .filter(|info| info.may_match(type_, description))
+            .map(|info| Self::new_impl(info.serial))
+            .find(|key| {
+                // Keys may disappear or become inaccessible while scanning.
+                key.description()
+                    .map(|desc| desc.type_ == type_ && desc.description == description)
+                    .unwrap_or(false)
+            });
+        let key = if let Some(key) = found {
+            key
+        } else {
+            return Err(request_err);
+        };
+
+        if let Some(destination) = destination {
+            destination.link_key(&key)?;
+        }
+
+        Ok(key)
+    }
+
     /// Determine whether the key is of a specific implementation or not.
     pub fn is_keytype<K>(&self) -> Result<bool>
     where
@@ -716,83 +1217,187 @@ impl Key {
         Ok(desc.type_ == K::name())
     }
 
+    /// Convert the key into a typed key if it is of the given key type.
+    ///
+    /// Returns `None` if the key has a different type.
+    pub fn downcast<K>(&self) -> Result<Option<TypedKey<K>>>
+    where
+        K: KeyType,
+    {
+        Ok(if self.is_keytype::<K>()? {
+            Some(TypedKey::new_impl(self.clone()))
+        } else {
+            None
+        })
+    }
+
     /// Update the payload in the key.
     pub fn update<K, P>(&mut self, payload: P) -> Result<()>
     where
         K: KeyType,
         P: Borrow<K::Payload>,
     {
-        keyctl_update(self.id, &payload.borrow().payload())
+        self.verify_identity()?;
+        let payload = payload.borrow().wiped_payload();
+        check_payload_size::<K>(&payload)
+            .and_then(|()| keyctl_update(self.id.into(), &payload))
+            .on(Operation::Update, self.id)
+            .map_err(|err| err.with_key_type(K::name()))
     }
 
-    /// Revokes the key. Requires `write` permission on the key.
+    /// Revokes the key. Requires `write` permission on the key. See `KeyHandle::revoke`.
     pub fn revoke(self) -> Result<()> {
-        Keyring::new_impl(self.id).revoke()
+        KeyHandle::revoke(self)
     }
 
     /// Change the user which owns the key.
     ///
     /// Requires the `setattr` permission on the key and the SysAdmin capability to change it to
-    /// anything other than the current user.
+    /// anything other than the current user. See `KeyHandle::chown`.
     pub fn chown(&mut self, uid: libc::uid_t) -> Result<()> {
-        Keyring::new_impl(self.id).chown(uid)
+        KeyHandle::chown(self, uid)
     }
 
     /// Change the group which owns the key.
     ///
     /// Requires the `setattr` permission on the key and the SysAdmin capability to change it to
-    /// anything other than a group of which the current user is a member.
+    /// anything other than a group of which the current user is a member. See
+    /// `KeyHandle::chgrp`.
     pub fn chgrp(&mut self, gid: libc::gid_t) -> Result<()> {
-        Keyring::new_impl(self.id).chgrp(gid)
+        KeyHandle::chgrp(self, gid)
     }
 
     /// Set the permissions on the key.
     ///
     /// Requires the `setattr` permission on the key and the SysAdmin capability if the current
-    /// user does not own the key.
+    /// user does not own the key. See `KeyHandle::set_permissions`.
     pub fn set_permissions(&mut self, perms: Permission) -> Result<()> {
-        Keyring::new_impl(self.id).set_permissions(perms)
+        KeyHandle::set_permissions(self, perms)
     }
 
     #[cfg(test)]
     pub(crate) fn set_permissions_raw(&mut self, perms: KeyPermissions) -> Result<()> {
-        Keyring::new_impl(self.id).set_permissions_raw(perms)
+        keyctl_setperm(self.id.into(), perms).on(Operation::SetPermissions, self.id)
     }
 
     /// Retrieve metadata about the key.
     ///
-    /// # Panics
-    ///
-    /// If the kernel returns malformed data, the parser will panic.
+    /// Malformed data from the kernel is reported as an `EINVAL` error. See
+    /// `KeyHandle::description`.
     pub fn description(&self) -> Result<Description> {
-        Keyring::new_impl(self.id).description()
+        KeyHandle::description(self)
     }
 
-    /// Read the payload of the key. Requires `read` permissions on the key.
-    pub fn read(&self) -> Result<Vec<u8>> {
-        read_impl(self.id)
-    }
-
-    /// Set an expiration timer on the keyring to `timeout`.
+    /// Set an expiration timer on the key to `timeout`.
     ///
     /// Any partial seconds are ignored. A timeout of 0 means "no expiration". Requires the
-    /// `setattr` permission on the key.
+    /// `setattr` permission on the key. See `KeyHandle::set_timeout`.
     pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
-        Keyring::new_impl(self.id).set_timeout(timeout)
+        KeyHandle::set_timeout(self, timeout)
     }
 
     /// The security context of the key.
     ///
-    /// Depends on the security manager loaded into the kernel (e.g., SELinux or AppArmor).
+    /// Depends on the security manager loaded into the kernel (e.g., SELinux or AppArmor). See
+    /// `KeyHandle::security`.
     pub fn security(&self) -> Result<String> {
-        Keyring::new_impl(self.id).security()
+        KeyHandle::security(self)
     }
 
     /// Invalidates the key and schedules it for removal.
     ///
-    /// Requires the `search` permission on the key.
+    /// Requires the `search` permission on the key. See `KeyHandle::invalidate`.
     pub fn invalidate(self) -> Result<()> {
-        Keyring::new_impl(self.id).invalidate()
+        KeyHandle::invalidate(self)
+    }
+
+    /// Retrieve the description of the key parsed for a specific key type.
+    ///
+    /// Fails with `EINVAL` if the key is not of the given type or its description cannot be
+    /// parsed. Requires `view` permission on the key.
+    pub fn typed_description<K>(&self) -> Result<<K::Description as ToOwned>::Owned>
+    where
+        K: KeyType,
+        K::Description: ToOwned,
+        <K::Description as ToOwned>::Owned: FromKeyDescription,
+    {
+        let desc = self
+            .description()
+            .map_err(|err| err.with_key_type(K::name()))?;
+        if desc.type_ == K::name() {
+            FromKeyDescription::from_description(&desc.description)
+        } else {
+            None
+        }
+        .ok_or_else(|| {
+            Error::new(Operation::Describe, errno::Errno(libc::EINVAL))
+                .with_serial(self.id)
+                .with_key_type(K::name())
+        })
+    }
+
+    /// Read the payload of the key. Requires `read` permissions on the key.
+    pub fn read(&self) -> Result<Vec<u8>> {
+        read_impl(self.id)
+    }
+
+    /// Read the payload of the key into a caller-provided buffer.
+    ///
+    /// The size of the payload is returned. If the buffer is too small, its contents are
+    /// unspecified and the call should be retried with a buffer of the returned size. Requires
+    /// `read` permissions on the key.
+    pub fn read_into(&self, buffer: &mut [u8]) -> Result<usize> {
+        keyctl_read(self.id.into(), Some(buffer.as_out())).on(Operation::Read, self.id)
+    }
+
+    /// Read the payload of the key into a `SecretBuffer`.
+    ///
+    /// The payload is never copied into memory which is not wiped. Requires `read` permissions on
+    /// the key.
+    pub fn read_secret(&self) -> Result<SecretBuffer> {
+        let mut sz = keyctl_read(self.id.into(), None).on(Operation::Read, self.id)?;
+        loop {
+            let mut buffer = SecretBuffer::new(sz);
+            // Any partial payload in an undersized buffer is wiped when it is dropped.
+            let actual = self.read_secret_into(&mut buffer)?;
+            if actual <= sz {
+                return Ok(buffer);
+            }
+            sz = actual;
+        }
+    }
+
+    /// Read the payload of the key into an existing `SecretBuffer`.
+    ///
+    /// The size of the payload is returned. If it fits, the length of the buffer is set to the
+    /// size of the payload. Otherwise, the buffer should be replaced with one of the returned size
+    /// and its contents are unspecified. Requires `read` permissions on the key.
+    pub fn read_secret_into(&self, buffer: &mut SecretBuffer) -> Result<usize> {
+        let sz = self.read_into(buffer.spare_storage())?;
+        if sz <= buffer.capacity() {
+            buffer.set_filled(sz);
+        }
+        Ok(sz)
+    }
+
+    /// Read the payload of the key into a reusable buffer.
+    ///
+    /// The existing capacity of the buffer is used before growing it, so reading keys of similar
+    /// sizes repeatedly does not allocate. The buffer holds the payload and its length is
+    /// returned. Requires `read` permissions on the key.
+    pub fn read_into_vec(&self, buffer: &mut Vec<u8>) -> Result<usize> {
+        read_into_vec_impl(self.id, buffer)
+    }
+
+    /// Read the payload of the key and decode it for a specific key type.
+    ///
+    /// The type of the key is not checked. Fails with `EINVAL` if the payload cannot be decoded.
+    /// Requires `read` permissions on the key.
+    pub fn read_as<K>(&self) -> Result<K::Contents>
+    where
+        K: ReadableKeyType,
+    {
+        read_as_impl::<K>(self.id)
     }
 
     /// Create an object to manage a key request.
@@ -805,30 +1410,23 @@ impl Key {
     ///
     /// See `KeyManager::request_key_auth_key`.
     pub fn manage(&mut self) -> Result<KeyManager> {
-        keyctl_assume_authority(Some(self.id))?;
-        Ok(KeyManager::new(Key::new_impl(self.id)))
+        self.verify_identity()?;
+        keyctl_assume_authority(Some(self.id.into())).on(Operation::AssumeAuthority, self.id)?;
+        Ok(KeyManager::new(self.clone()))
     }
 
     /// Compute a Diffie-Hellman prime for use as a shared secret or public key.
     pub fn compute_dh(private: &Key, prime: &Key, base: &Key) -> Result<Vec<u8>> {
-        // Get the size of the description.
-        let mut sz = keyctl_dh_compute(private.id, prime.id, base.id, None)?;
-        // Allocate this description.
-        let mut buffer = vec![0; sz];
-        loop {
-            let write_buffer = buffer.get_backing_buffer();
-            // Fetch the description.
-            sz = keyctl_dh_compute(private.id, prime.id, base.id, Some(write_buffer))?;
-
-            // If we got everything, exit.
-            if sz <= buffer.capacity() {
-                break;
-            }
-
-            // Resize for the additional capacity we need.
-            buffer.resize(sz, 0);
-        }
-        buffer.truncate(sz);
+        let mut buffer = Vec::new();
+        kernel_buffer(&mut buffer, |write_buffer| {
+            keyctl_dh_compute(
+                private.id.into(),
+                prime.id.into(),
+                base.id.into(),
+                write_buffer,
+            )
+            .on(Operation::DhCompute, private.id)
+        })?;
         Ok(buffer)
     }
 
@@ -866,37 +1464,23 @@ impl Key {
         hash: KeyctlHash,
         other: Option<&[u8]>,
     ) -> Result<Vec<u8>> {
-        // Get the size of the description.
-        let mut sz =
-            keyctl_dh_compute_kdf(private.id, prime.id, base.id, hash.hash(), other, None)?;
-        // Allocate this description.
-        let mut buffer = vec![0; sz];
-        loop {
-            let write_buffer = buffer.get_backing_buffer();
-            // Fetch the description.
-            sz = keyctl_dh_compute_kdf(
-                private.id,
-                prime.id,
-                base.id,
+        let mut buffer = Vec::new();
+        kernel_buffer(&mut buffer, |write_buffer| {
+            keyctl_dh_compute_kdf(
+                private.id.into(),
+                prime.id.into(),
+                base.id.into(),
                 hash.hash(),
                 other,
-                Some(write_buffer),
-            )?;
-
-            // If we got everything, exit.
-            if sz <= buffer.capacity() {
-                break;
-            }
-
-            // Resize for the additional capacity we need.
-            buffer.resize(sz, 0);
-        }
-        buffer.truncate(sz);
+                write_buffer,
+            )
+            .on(Operation::DhCompute, private.id)
+        })?;
         Ok(buffer)
     }
 
     fn pkey_query_support_impl(&self, info: &str) -> Result<PKeyQuery> {
-        keyctl_pkey_query(self.id, info)
+        keyctl_pkey_query(self.id.into(), info).on(Operation::PKeyQuery, self.id)
     }
 
     /// Query which optionally supported features may be used by the key.
@@ -912,7 +1496,8 @@ impl Key {
         let support = self.pkey_query_support_impl(&info)?;
         let mut buffer = Vec::with_capacity(support.max_enc_size as usize);
         let write_buffer = buffer.get_backing_buffer();
-        let sz = keyctl_pkey_encrypt(self.id, &info, data, write_buffer)?;
+        let sz = keyctl_pkey_encrypt(self.id.into(), &info, data, write_buffer)
+            .on(Operation::PKeyEncrypt, self.id)?;
         buffer.truncate(sz);
         Ok(buffer)
     }
@@ -923,7 +1508,8 @@ impl Key {
         let support = self.pkey_query_support_impl(&info)?;
         let mut buffer = Vec::with_capacity(support.max_dec_size as usize);
         let write_buffer = buffer.get_backing_buffer();
-        let sz = keyctl_pkey_decrypt(self.id, &info, data, write_buffer)?;
+        let sz = keyctl_pkey_decrypt(self.id.into(), &info, data, write_buffer)
+            .on(Operation::PKeyDecrypt, self.id)?;
         buffer.truncate(sz);
         Ok(buffer)
     }
@@ -934,7 +1520,8 @@ impl Key {
         let support = self.pkey_query_support_impl(&info)?;
         let mut buffer = Vec::with_capacity(support.max_sig_size as usize);
         let write_buffer = buffer.get_backing_buffer();
-        let sz = keyctl_pkey_sign(self.id, &info, data, write_buffer)?;
+        let sz = keyctl_pkey_sign(self.id.into(), &info, data, write_buffer)
+            .on(Operation::PKeySign, self.id)?;
         buffer.truncate(sz);
         Ok(buffer)
     }
@@ -946,7 +1533,192 @@ impl Key {
         data: &[u8],
         signature: &[u8],
     ) -> Result<bool> {
-        keyctl_pkey_verify(self.id, &options.info(), data, signature)
+        keyctl_pkey_verify(self.id.into(), &options.info(), data, signature)
+            .on(Operation::PKeyVerify, self.id)
+    }
+}
+
+/// A kernel key with a known key type.
+///
+/// Methods which depend on the type of the key are bound to `K` so that, for example, a payload
+/// for another key type cannot be used to update the key. Untyped methods which only inspect the
+/// key are available through `Deref` to `Key`; `into_key` may be used to forget the type.
+pub struct TypedKey<K> {
+    key: Key,
+    _type: PhantomData<fn() -> K>,
+}
+
+impl<K> TypedKey<K>
+where
+    K: KeyType,
+{
+    pub(crate) fn new_impl(key: Key) -> Self {
+        TypedKey {
+            key,
+            _type: PhantomData,
+        }
+    }
+
+    /// Update the payload in the key.
+    pub fn update<P>(&mut self, payload: P) -> Result<()>
+    where
+        P: Borrow<K::Payload>,
+    {
+        self.key.update::<K, P>(payload)
+    }
+
+    /// Manage the key on the current thread.
+    ///
+    /// See `Key::manage`.
+    pub fn manage(&mut self) -> Result<KeyManager> {
+        self.key.manage()
+    }
+
+    /// Retrieve metadata about the key.
+    ///
+    /// Malformed data from the kernel is reported as an `EINVAL` error. See
+    /// `KeyHandle::description`.
+    pub fn description(&self) -> Result<Description> {
+        KeyHandle::description(self)
+    }
+
+    /// Revokes the key. Requires `write` permission on the key. See `KeyHandle::revoke`.
+    pub fn revoke(self) -> Result<()> {
+        KeyHandle::revoke(self)
+    }
+
+    /// Change the user which owns the key. See `Key::chown`.
+    pub fn chown(&mut self, uid: libc::uid_t) -> Result<()> {
+        KeyHandle::chown(self, uid)
+    }
+
+    /// Change the group which owns the key. See `Key::chgrp`.
+    pub fn chgrp(&mut self, gid: libc::gid_t) -> Result<()> {
+        KeyHandle::chgrp(self, gid)
+    }
+
+    /// Set the permissions on the key. See `Key::set_permissions`.
+    pub fn set_permissions(&mut self, perms: Permission) -> Result<()> {
+        KeyHandle::set_permissions(self, perms)
+    }
+
+    /// Set an expiration timer on the key to `timeout`. See `Key::set_timeout`.
+    pub fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        KeyHandle::set_timeout(self, timeout)
+    }
+
+    /// Invalidates the key and schedules it for removal. Requires the `search` permission on the
+    /// key. See `KeyHandle::invalidate`.
+    pub fn invalidate(self) -> Result<()> {
+        KeyHandle::invalidate(self)
+    }
+
+    /// Retrieve the description of the key parsed as a `K::Description`.
+    pub fn typed_description(&self) -> Result<<K::Description as ToOwned>::Owned>
+    where
+        K::Description: ToOwned,
+        <K::Description as ToOwned>::Owned: FromKeyDescription,
+    {
+        self.key.typed_description::<K>()
+    }
+
+    /// Forget the type of the key.
+    pub fn into_key(self) -> Key {
+        self.key
+    }
+}
+
+impl<K> TypedKey<K>
+where
+    K: ReadableKeyType,
+{
+    /// Read and decode the payload of the key. Requires `read` permissions on the key.
+    pub fn read(&self) -> Result<K::Contents> {
+        self.key.read_as::<K>()
+    }
+}
+
+impl<K> KeyHandle for TypedKey<K>
+where
+    K: KeyType,
+{
+    fn serial(&self) -> KeyringSerial {
+        self.key.id
+    }
+
+    fn identity(&self) -> Option<&KeyIdentity> {
+        self.key.identity()
+    }
+
+    fn set_identity(&mut self, identity: Option<KeyIdentity>) {
+        self.key.set_identity(identity)
+    }
+
+    fn description(&self) -> Result<Description> {
+        self.key
+            .description()
+            .map_err(|err| err.with_key_type(K::name()))
+    }
+}
+
+impl<K> Deref for TypedKey<K> {
+    type Target = Key;
+
+    fn deref(&self) -> &Key {
+        &self.key
+    }
+}
+
+impl<K> AsRef<Key> for TypedKey<K> {
+    fn as_ref(&self) -> &Key {
+        &self.key
+    }
+}
+
+impl<K> From<TypedKey<K>> for Key {
+    fn from(key: TypedKey<K>) -> Self {
+        key.key
+    }
+}
+
+impl<K> fmt::Debug for TypedKey<K>
+where
+    K: KeyType,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TypedKey")
+            .field("key", &self.key)
+            .field("type", &K::name())
+            .finish()
+    }
+}
+
+impl<K> Clone for TypedKey<K> {
+    fn clone(&self) -> Self {
+        TypedKey {
+            key: self.key.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<K> PartialEq for TypedKey<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K> Eq for TypedKey<K> {}
+
+impl<K> PartialEq<Key> for TypedKey<K> {
+    fn eq(&self, other: &Key) -> bool {
+        self.key == *other
+    }
+}
+
+impl<K> PartialEq<TypedKey<K>> for Key {
+    fn eq(&self, other: &TypedKey<K>) -> bool {
+        *self == other.key
     }
 }
 
@@ -966,39 +1738,41 @@ pub struct Description {
 }
 
 impl Description {
-    fn parse(desc: &str) -> Option<Description> {
-        let mut pieces = desc.split(';').collect::<Vec<_>>();
-        // Reverse the string because the kernel plans to extend it by adding fields to the
-        // beginning of the string. By doing this, the fields are at a constant position in the
-        // split string.
-        pieces.reverse();
-        let len = pieces.len();
-        if len < 5 {
-            None
-        } else {
-            if len > 5 {
-                error!(
-                    "New fields detected! Please report this upstream to \
-                     https://github.com/mathstuf/rust-keyutils: {}",
-                    desc,
-                );
-            }
-            let bits = KeyPermissions::from_str_radix(pieces[1], 16).unwrap();
-            if Permission::from_bits(bits).is_none() {
-                error!(
-                    "New permission bits detected! Please report this upstream to \
-                     https://github.com/mathstuf/rust-keyutils: {}",
-                    bits,
-                );
-            }
-            Some(Description {
-                type_: pieces[4].to_owned(),
-                uid: pieces[3].parse::<libc::uid_t>().unwrap(),
-                gid: pieces[2].parse::<libc::gid_t>().unwrap(),
-                perms: Permission::from_bits_truncate(bits),
-                description: pieces[0].to_owned(),
-            })
+    pub(crate) fn parse(desc: &str) -> Option<Description> {
+        // The kernel formats descriptions as `type;uid;gid;perm;description`. Only the type and
+        // numeric fields are free of `;`; the description itself may contain any number of them.
+        let mut pieces = desc.splitn(5, ';');
+        let type_ = pieces.next()?;
+        let uid = Self::parse_id(pieces.next()?)?;
+        let gid = Self::parse_id(pieces.next()?)?;
+        let bits = KeyPermissions::from_str_radix(pieces.next()?, 16).ok()?;
+        let description = pieces.next()?;
+
+        if Permission::from_bits(bits).is_none() {
+            error!(
+                "New permission bits detected! Please report this upstream to \
+                 https://github.com/mathstuf/rust-keyutils: {}",
+                bits,
+            );
         }
+
+        Some(Description {
+            type_: type_.to_owned(),
+            uid,
+            gid,
+            perms: Permission::from_bits_truncate(bits),
+            description: description.to_owned(),
+        })
+    }
+
+    // The kernel prints IDs with `%d`, so IDs above `i32::MAX` (including `-1`) show up as
+    // negative numbers.
+    fn parse_id(id: &str) -> Option<libc::uid_t> {
+        let id = id.parse::<i64>().ok()?;
+        if id < i64::from(i32::MIN) || i64::from(u32::MAX) < id {
+            return None;
+        }
+        Some(id as libc::uid_t)
     }
 }
 
@@ -1012,10 +1786,10 @@ pub enum TargetKeyring<'a> {
 }
 
 impl<'a> TargetKeyring<'a> {
-    fn serial(self) -> KeyringSerial {
+    fn spec(self) -> KeySpec {
         match self {
-            TargetKeyring::Special(special) => special.serial(),
-            TargetKeyring::Keyring(keyring) => keyring.id,
+            TargetKeyring::Special(special) => special.spec(),
+            TargetKeyring::Keyring(keyring) => keyring.id.into(),
         }
     }
 }
@@ -1066,14 +1840,16 @@ impl KeyManager {
     ///
     /// This key must be present in an available keyring before `Key::manage` may be called.
     pub fn request_key_auth_key(create: bool) -> Result<Key> {
-        keyctl_get_keyring_id(KEY_SPEC_REQKEY_AUTH_KEY, create).map(Key::new_impl)
+        keyctl_get_keyring_id(KEY_SPEC_REQKEY_AUTH_KEY, create)
+            .on(Operation::GetKeyringId, KEY_SPEC_REQKEY_AUTH_KEY)
+            .map(Key::new_impl)
     }
 
     /// Drop authority for the current thread.
     ///
     /// This invalidates
     pub fn drop_authority() -> Result<()> {
-        keyctl_assume_authority(None)
+        keyctl_assume_authority(None).op(Operation::AssumeAuthority)
     }
 
     /// Instantiate the key with the given payload.
@@ -1083,44 +1859,91 @@ impl KeyManager {
         P: AsRef<[u8]>,
     {
         keyctl_instantiate(
-            self.key.id,
+            self.key.id.into(),
             payload.as_ref(),
-            keyring.into().map(TargetKeyring::serial),
+            keyring.into().map(TargetKeyring::spec),
         )
+        .on(Operation::Instantiate, self.key.id)
     }
 
     /// Reject the key with the given `error`.
     ///
-    /// Requests for the key will fail until `timeout` has elapsed (partial
-    /// seconds are ignored). This is to prevent a denial-of-service by
-    /// requesting a non-existant key repeatedly. The requester must have
-    /// `write` permission on the keyring.
+    /// Requests for the key will fail until `timeout` has elapsed (rounded
+    /// down, but at least a second if non-zero; see `TimeoutRounding`). This is
+    /// to prevent a denial-of-service by requesting a non-existant key
+    /// repeatedly. The requester must have `write` permission on the keyring.
     pub fn reject<'a, T>(self, keyring: T, timeout: Duration, error: errno::Errno) -> Result<()>
     where
         T: Into<Option<TargetKeyring<'a>>>,
     {
         keyctl_reject(
-            self.key.id,
-            timeout.as_secs() as TimeoutSeconds,
+            self.key.id.into(),
+            TimeoutRounding::Down.seconds(timeout),
             error,
-            keyring.into().map(TargetKeyring::serial),
+            keyring.into().map(TargetKeyring::spec),
         )
+        .on(Operation::Reject, self.key.id)
     }
 
     /// Reject the key with `ENOKEY`.
     ///
-    /// Requests for the key will fail until `timeout` has elapsed (partial
-    /// seconds are ignored). This is to prevent a denial-of-service by
-    /// requesting a non-existant key repeatedly. The requester must have
-    /// `write` permission on the keyring.
+    /// Requests for the key will fail until `timeout` has elapsed (rounded
+    /// down, but at least a second if non-zero; see `TimeoutRounding`). This is
+    /// to prevent a denial-of-service by requesting a non-existant key
+    /// repeatedly. The requester must have `write` permission on the keyring.
     pub fn negate<'a, T>(self, keyring: T, timeout: Duration) -> Result<()>
     where
         T: Into<Option<TargetKeyring<'a>>>,
     {
         keyctl_negate(
-            self.key.id,
-            timeout.as_secs() as TimeoutSeconds,
-            keyring.into().map(TargetKeyring::serial),
+            self.key.id.into(),
+            TimeoutRounding::Down.seconds(timeout),
+            keyring.into().map(TargetKeyring::spec),
         )
+        .on(Operation::Negate, self.key.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Permission;
+
+    use super::Description;
+
+    #[test]
+    fn test_description_parse() {
+        let desc = Description::parse("user;1000;100;3f010000;some;description").unwrap();
+        assert_eq!(desc.type_, "user");
+        assert_eq!(desc.uid, 1000);
+        assert_eq!(desc.gid, 100);
+        assert_eq!(
+            desc.perms,
+            Permission::POSSESSOR_ALL | Permission::USER_VIEW,
+        );
+        assert_eq!(desc.description, "some;description");
+    }
+
+    #[test]
+    fn test_description_parse_empty_description() {
+        let desc = Description::parse("keyring;0;0;3f3f0000;").unwrap();
+        assert_eq!(desc.type_, "keyring");
+        assert_eq!(desc.description, "");
+    }
+
+    #[test]
+    fn test_description_parse_negative_ids() {
+        let desc = Description::parse("user;-1;-2;3f010000;desc").unwrap();
+        assert_eq!(desc.uid, libc::uid_t::MAX);
+        assert_eq!(desc.gid, libc::gid_t::MAX - 1);
+    }
+
+    #[test]
+    fn test_description_parse_malformed() {
+        assert!(Description::parse("").is_none());
+        assert!(Description::parse("user;1000;100;3f010000").is_none());
+        assert!(Description::parse("user;uid;100;3f010000;desc").is_none());
+        assert!(Description::parse("user;1000;4294967296;3f010000;desc").is_none());
+        assert!(Description::parse("user;1000;100;perms;desc").is_none());
+        assert!(Description::parse("user;1000;100;1ffffffff;desc").is_none());
     }
 }
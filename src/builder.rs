@@ -0,0 +1,324 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use keyutils_raw::*;
+
+use crate::api::{check_payload_size, Keyring, TypedKey};
+use crate::constants::{MoveFlags, Permission, SpecialKeyring};
+use crate::error::{ErrnoContext, Operation, Result};
+use crate::handle::KeyHandle;
+use crate::keytype::KeyType;
+use crate::secret::{Redacted, WipedPayload};
+
+/// Attributes to apply to a key or keyring once it has been created.
+#[derive(Debug, Clone, Default)]
+struct Attributes {
+    permissions: Option<Permission>,
+    owner: Option<(libc::uid_t, libc::gid_t)>,
+    timeout: Option<Duration>,
+}
+
+impl Attributes {
+    /// The permissions to set on the handle, if any.
+    fn permissions<H>(&self, handle: &H, mask: Permission) -> Result<Option<Permission>>
+    where
+        H: KeyHandle,
+    {
+        Ok(match self.permissions {
+            Some(permissions) => Some(permissions - mask),
+            None if !mask.is_empty() => Some(handle.description()?.perms - mask),
+            None => None,
+        })
+    }
+
+    fn apply<H>(&self, handle: &mut H, permissions: Option<Permission>) -> Result<()>
+    where
+        H: KeyHandle,
+    {
+        // Permissions are set last since they may revoke the ability to set the other attributes.
+        if let Some(timeout) = self.timeout {
+            handle.set_timeout(timeout)?;
+        }
+        if let Some((uid, gid)) = self.owner {
+            let id = handle.serial();
+            keyctl_chown(id.into(), Some(uid), Some(gid)).on(Operation::Chown, id)?;
+        }
+        if let Some(permissions) = permissions {
+            handle.set_permissions(permissions)?;
+        }
+        Ok(())
+    }
+}
+
+/// A keyring private to the current thread in which keys are built.
+///
+/// Keys are created in the staging keyring and only moved into their destination once their
+/// attributes have been set, so they are never reachable with the kernel's default attributes.
+/// The staging keyring is invalidated when dropped.
+struct Staging {
+    keyring: Keyring,
+}
+
+impl Staging {
+    /// Permissions the possessor needs to move a key out of the staging keyring and then set
+    /// its final permissions. Keys are only possessed if they may be searched for.
+    const MOVE_PERMISSIONS: Permission = Permission::from_bits_truncate(
+        Permission::POSSESSOR_SEARCH.bits()
+            | Permission::POSSESSOR_LINK.bits()
+            | Permission::POSSESSOR_SET_ATTRIBUTE.bits(),
+    );
+
+    fn new() -> Result<Self> {
+        let mut thread = Keyring::attach_or_create(SpecialKeyring::Thread)?;
+        let keyring = thread.add_keyring("keyutils:staging")?;
+        Ok(Staging {
+            keyring,
+        })
+    }
+
+    fn finish_impl<H>(
+        &self,
+        handle: &mut H,
+        attributes: &Attributes,
+        mask: Permission,
+        destination: &Keyring,
+        flags: MoveFlags,
+    ) -> Result<()>
+    where
+        H: KeyHandle,
+    {
+        let permissions = attributes.permissions(handle, mask)?;
+        // Moving the key requires the `link` permission, so any permissions needed for the move
+        // are only dropped once the key is in place.
+        let staged = permissions.map(|permissions| permissions | Self::MOVE_PERMISSIONS);
+        attributes.apply(handle, staged)?;
+
+        let id = handle.serial();
+        let to = destination.serial();
+        keyctl_move(
+            id.into(),
+            self.keyring.serial().into(),
+            to.into(),
+            flags.bits(),
+        )
+        .on(Operation::Move, to)?;
+
+        match permissions {
+            Some(permissions) if Some(permissions) != staged => handle.set_permissions(permissions),
+            _ => Ok(()),
+        }
+    }
+
+    /// Apply the attributes and move the handle into its destination, invalidating it on
+    /// failure.
+    fn finish<H>(
+        &self,
+        mut handle: H,
+        attributes: &Attributes,
+        mask: Permission,
+        destination: &Keyring,
+        flags: MoveFlags,
+    ) -> Result<H>
+    where
+        H: KeyHandle,
+    {
+        if let Err(err) = self.finish_impl(&mut handle, attributes, mask, destination, flags) {
+            let id = handle.serial();
+            // Report the original error; the key is unusable either way.
+            let _ = keyctl_invalidate(id.into());
+            return Err(err);
+        }
+        Ok(handle)
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        let _ = keyctl_invalidate(self.keyring.serial().into());
+    }
+}
+
+/// A builder for a key which sets its attributes as soon as it is created.
+///
+/// The key is built in a keyring private to the current thread and only linked into the
+/// destination keyring once its attributes have been set. Unlike `Keyring::add_key`, an existing
+/// key with the same description is never updated since the attributes would then be applied to
+/// a key the builder did not create. If any attribute cannot be set, the key is invalidated and
+/// the error is returned. The keyring's permission mask (see `Keyring::set_permission_mask`) is
+/// applied to the key's permissions.
+///
+/// Moving the key requires the possessor to be able to search, link, and set attributes on it.
+/// Any of these which are not part of the requested permissions are removed after the move,
+/// which requires the destination keyring to be possessed.
+///
+/// See `Keyring::create`.
+#[must_use]
+pub struct KeyBuilder<'a, K> {
+    keyring: &'a mut Keyring,
+    description: String,
+    payload: WipedPayload<'static>,
+    attributes: Attributes,
+    _type: PhantomData<fn() -> K>,
+}
+
+impl<'a, K> KeyBuilder<'a, K>
+where
+    K: KeyType,
+{
+    pub(crate) fn new(
+        keyring: &'a mut Keyring,
+        description: String,
+        payload: WipedPayload<'static>,
+    ) -> Self {
+        KeyBuilder {
+            keyring,
+            description,
+            payload,
+            attributes: Attributes::default(),
+            _type: PhantomData,
+        }
+    }
+
+    /// Set the permissions of the key.
+    pub fn permissions(mut self, permissions: Permission) -> Self {
+        self.attributes.permissions = Some(permissions);
+        self
+    }
+
+    /// Set the user and group which own the key.
+    pub fn owner(mut self, uid: libc::uid_t, gid: libc::gid_t) -> Self {
+        self.attributes.owner = Some((uid, gid));
+        self
+    }
+
+    /// Set an expiration timer on the key.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.attributes.timeout = Some(timeout);
+        self
+    }
+
+    /// Create the key and set its attributes.
+    ///
+    /// Fails with `EEXIST` if the keyring already contains a key with the same type and
+    /// description. Requires `write` permission on the keyring.
+    pub fn add(self) -> Result<TypedKey<K>> {
+        check_payload_size::<K>(&self.payload)
+            .on(Operation::AddKey, self.keyring.serial())
+            .map_err(|err| err.with_key_type(K::name()))?;
+        self.keyring.verify_identity()?;
+        let mask = self.keyring.permission_mask();
+        let mut staging = Staging::new()?;
+        let key = staging
+            .keyring
+            .add_key_dyn(K::name(), &self.description, &self.payload)?;
+        staging
+            .finish(
+                key,
+                &self.attributes,
+                mask,
+                self.keyring,
+                MoveFlags::EXCLUSIVE,
+            )
+            .map(TypedKey::new_impl)
+            .map_err(|err| err.with_key_type(K::name()))
+    }
+}
+
+impl<K> fmt::Debug for KeyBuilder<'_, K>
+where
+    K: KeyType,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("KeyBuilder")
+            .field("keyring", &self.keyring)
+            .field("type", &K::name())
+            .field("description", &self.description)
+            .field("payload", &Redacted)
+            .field("attributes", &self.attributes)
+            .finish()
+    }
+}
+
+/// A builder for a keyring which sets its attributes as soon as it is created.
+///
+/// Like `Keyring::add_keyring`, any existing keyring with the same description is unlinked
+/// once the new keyring is ready. The new keyring inherits the permission mask of the keyring
+/// it is created in. See `KeyBuilder` and `Keyring::create_keyring`.
+#[derive(Debug)]
+#[must_use]
+pub struct KeyringBuilder<'a> {
+    keyring: &'a mut Keyring,
+    description: String,
+    attributes: Attributes,
+}
+
+impl<'a> KeyringBuilder<'a> {
+    pub(crate) fn new(keyring: &'a mut Keyring, description: String) -> Self {
+        KeyringBuilder {
+            keyring,
+            description,
+            attributes: Attributes::default(),
+        }
+    }
+
+    /// Set the permissions of the keyring.
+    pub fn permissions(mut self, permissions: Permission) -> Self {
+        self.attributes.permissions = Some(permissions);
+        self
+    }
+
+    /// Set the user and group which own the keyring.
+    pub fn owner(mut self, uid: libc::uid_t, gid: libc::gid_t) -> Self {
+        self.attributes.owner = Some((uid, gid));
+        self
+    }
+
+    /// Set an expiration timer on the keyring.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.attributes.timeout = Some(timeout);
+        self
+    }
+
+    /// Create the keyring and set its attributes.
+    pub fn add(self) -> Result<Keyring> {
+        self.keyring.verify_identity()?;
+        let mask = self.keyring.permission_mask();
+        let mut staging = Staging::new()?;
+        let mut keyring = staging.keyring.add_keyring(self.description.as_str())?;
+        keyring.set_permission_mask(mask);
+        staging.finish(
+            keyring,
+            &self.attributes,
+            mask,
+            self.keyring,
+            MoveFlags::empty(),
+        )
+    }
+}
@@ -24,6 +24,9 @@
 // (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::convert::TryInto;
+use std::time::Duration;
+
 use bitflags::bitflags;
 use keyutils_raw::*;
 
@@ -43,11 +46,32 @@ pub enum SpecialKeyring {
     UserSession,
     /// A group-specific keyring.
     Group,
+    /// The destination keyring of the `request_key` call being serviced.
+    ///
+    /// Only available to a process instantiating a key (see `KeyManager`).
+    Requestor,
 }
 
 impl SpecialKeyring {
+    /// All special keyrings.
+    pub const ALL: [SpecialKeyring; 7] = [
+        SpecialKeyring::Thread,
+        SpecialKeyring::Process,
+        SpecialKeyring::Session,
+        SpecialKeyring::User,
+        SpecialKeyring::UserSession,
+        SpecialKeyring::Group,
+        SpecialKeyring::Requestor,
+    ];
+
     /// Retrieve the serial number for the special keyring.
+    #[deprecated(note = "special IDs are not serials; use `spec` instead")]
     pub fn serial(self) -> KeyringSerial {
+        KeyringSerial::new(self.spec().get()).expect("special IDs are non-zero")
+    }
+
+    /// Retrieve the ID the kernel uses for the special keyring.
+    pub fn spec(self) -> KeySpec {
         match self {
             SpecialKeyring::Thread => KEY_SPEC_THREAD_KEYRING,
             SpecialKeyring::Process => KEY_SPEC_PROCESS_KEYRING,
@@ -55,6 +79,62 @@ impl SpecialKeyring {
             SpecialKeyring::User => KEY_SPEC_USER_KEYRING,
             SpecialKeyring::UserSession => KEY_SPEC_USER_SESSION_KEYRING,
             SpecialKeyring::Group => KEY_SPEC_GROUP_KEYRING,
+            SpecialKeyring::Requestor => KEY_SPEC_REQUESTOR_KEYRING,
+        }
+    }
+}
+
+impl From<SpecialKeyring> for KeySpec {
+    fn from(special: SpecialKeyring) -> Self {
+        special.spec()
+    }
+}
+
+/// Keyrings created by the kernel for its own use.
+///
+/// These keyrings hold the keys the kernel trusts (or distrusts) for verifying signatures. Which
+/// keyrings exist depends on the kernel's configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// #[non_exhaustive]
+pub enum SystemKeyring {
+    /// Keys built into the kernel image (`.builtin_trusted_keys`).
+    BuiltinTrusted,
+    /// Keys added at runtime which are signed by a trusted key (`.secondary_trusted_keys`).
+    SecondaryTrusted,
+    /// Keys provided by the platform firmware (`.platform`).
+    Platform,
+    /// Machine owner keys (`.machine`).
+    Machine,
+    /// Hashes and keys which are explicitly distrusted (`.blacklist`).
+    Blacklist,
+    /// Keys used by the integrity measurement architecture (`.ima`).
+    Ima,
+    /// Keys used by the extended verification module (`.evm`).
+    Evm,
+}
+
+impl SystemKeyring {
+    /// All of the system keyrings.
+    pub const ALL: &'static [Self] = &[
+        SystemKeyring::BuiltinTrusted,
+        SystemKeyring::SecondaryTrusted,
+        SystemKeyring::Platform,
+        SystemKeyring::Machine,
+        SystemKeyring::Blacklist,
+        SystemKeyring::Ima,
+        SystemKeyring::Evm,
+    ];
+
+    /// The description of the keyring.
+    pub fn name(self) -> &'static str {
+        match self {
+            SystemKeyring::BuiltinTrusted => ".builtin_trusted_keys",
+            SystemKeyring::SecondaryTrusted => ".secondary_trusted_keys",
+            SystemKeyring::Platform => ".platform",
+            SystemKeyring::Machine => ".machine",
+            SystemKeyring::Blacklist => ".blacklist",
+            SystemKeyring::Ima => ".ima",
+            SystemKeyring::Evm => ".evm",
         }
     }
 }
@@ -146,6 +226,51 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Flags for moving a key or keyring between keyrings.
+    pub struct MoveFlags: u32 {
+        /// Fail with `EEXIST` if the destination keyring already contains a key or keyring of the
+        /// same type and description rather than displacing it.
+        const EXCLUSIVE = KEYCTL_MOVE_EXCL;
+    }
+}
+
+/// How to round a timeout to the whole seconds used by the kernel.
+///
+/// Since the kernel treats a timeout of zero as "no expiration", a non-zero timeout is never
+/// rounded to zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutRounding {
+    /// Ignore partial seconds.
+    Down,
+    /// Count partial seconds as a whole second.
+    Up,
+    /// Round to the nearest second.
+    Nearest,
+}
+
+impl TimeoutRounding {
+    /// The number of seconds to give to the kernel for `timeout`.
+    ///
+    /// Timeouts too long for the kernel are clamped to the longest possible timeout.
+    pub fn seconds(self, timeout: Duration) -> TimeoutSeconds {
+        if timeout == Duration::from_secs(0) {
+            return 0;
+        }
+
+        let secs = timeout.as_secs();
+        let secs = match self {
+            TimeoutRounding::Down => secs,
+            TimeoutRounding::Up if timeout.subsec_nanos() > 0 => secs.saturating_add(1),
+            TimeoutRounding::Nearest if timeout.subsec_nanos() >= 500_000_000 => {
+                secs.saturating_add(1)
+            },
+            TimeoutRounding::Up | TimeoutRounding::Nearest => secs,
+        };
+        secs.max(1).try_into().unwrap_or(TimeoutSeconds::MAX)
+    }
+}
+
 /// They kernel type for representing support for optional features.
 ///
 /// Asymmetric keys might only support a limited set of operations. These flags indicate which
@@ -163,15 +288,24 @@ bitflags! {
 
 #[test]
 fn test_keyring_ids() {
-    assert_eq!(SpecialKeyring::Thread.serial(), KEY_SPEC_THREAD_KEYRING);
-    assert_eq!(SpecialKeyring::Process.serial(), KEY_SPEC_PROCESS_KEYRING);
-    assert_eq!(SpecialKeyring::Session.serial(), KEY_SPEC_SESSION_KEYRING);
-    assert_eq!(SpecialKeyring::User.serial(), KEY_SPEC_USER_KEYRING);
+    assert_eq!(SpecialKeyring::Thread.spec(), KEY_SPEC_THREAD_KEYRING);
+    assert_eq!(SpecialKeyring::Process.spec(), KEY_SPEC_PROCESS_KEYRING);
+    assert_eq!(SpecialKeyring::Session.spec(), KEY_SPEC_SESSION_KEYRING);
+    assert_eq!(SpecialKeyring::User.spec(), KEY_SPEC_USER_KEYRING);
     assert_eq!(
-        SpecialKeyring::UserSession.serial(),
+        SpecialKeyring::UserSession.spec(),
         KEY_SPEC_USER_SESSION_KEYRING
     );
-    assert_eq!(SpecialKeyring::Group.serial(), KEY_SPEC_GROUP_KEYRING);
+    assert_eq!(SpecialKeyring::Group.spec(), KEY_SPEC_GROUP_KEYRING);
+    assert_eq!(SpecialKeyring::Requestor.spec(), KEY_SPEC_REQUESTOR_KEYRING);
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_keyring_serials() {
+    for &special in SpecialKeyring::ALL.iter() {
+        assert_eq!(special.serial().get(), special.spec().get());
+    }
 }
 
 #[test]
@@ -218,6 +352,38 @@ fn test_other_permission_bits() {
     assert_eq!(Permission::OTHER_ALL.bits, KEY_OTH_ALL);
 }
 
+#[test]
+fn test_timeout_rounding() {
+    let check = |rounding: TimeoutRounding, millis, expected| {
+        assert_eq!(rounding.seconds(Duration::from_millis(millis)), expected);
+    };
+
+    for &rounding in &[
+        TimeoutRounding::Down,
+        TimeoutRounding::Up,
+        TimeoutRounding::Nearest,
+    ] {
+        check(rounding, 0, 0);
+        check(rounding, 1, 1);
+        check(rounding, 900, 1);
+        check(rounding, 1000, 1);
+        assert_eq!(
+            rounding.seconds(Duration::from_secs(u64::MAX)),
+            TimeoutSeconds::MAX,
+        );
+    }
+
+    check(TimeoutRounding::Down, 2999, 2);
+    check(TimeoutRounding::Up, 2001, 3);
+    check(TimeoutRounding::Nearest, 2499, 2);
+    check(TimeoutRounding::Nearest, 2500, 3);
+}
+
+#[test]
+fn test_move_flags() {
+    assert_eq!(MoveFlags::EXCLUSIVE.bits, KEYCTL_MOVE_EXCL);
+}
+
 #[test]
 fn test_support_flags() {
     assert_eq!(
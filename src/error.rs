@@ -0,0 +1,464 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::borrow::Cow;
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+
+use keyutils_raw::{KeySpec, KeyringSerial};
+
+use crate::proc::QuotaCheck;
+
+/// The operation which was being performed when an error occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// #[non_exhaustive]
+pub enum Operation {
+    /// Adding a key to a keyring (`add_key`).
+    AddKey,
+    /// Requesting a key (`request_key`).
+    RequestKey,
+    /// Looking up a special keyring (`KEYCTL_GET_KEYRING_ID`).
+    GetKeyringId,
+    /// Joining a session keyring (`KEYCTL_JOIN_SESSION_KEYRING`).
+    JoinSessionKeyring,
+    /// Updating the payload of a key (`KEYCTL_UPDATE`).
+    Update,
+    /// Revoking a key (`KEYCTL_REVOKE`).
+    Revoke,
+    /// Changing the ownership of a key (`KEYCTL_CHOWN`).
+    Chown,
+    /// Changing the permissions of a key (`KEYCTL_SETPERM`).
+    SetPermissions,
+    /// Describing a key (`KEYCTL_DESCRIBE`).
+    Describe,
+    /// Clearing a keyring (`KEYCTL_CLEAR`).
+    Clear,
+    /// Linking a key into a keyring (`KEYCTL_LINK`).
+    Link,
+    /// Unlinking a key from a keyring (`KEYCTL_UNLINK`).
+    Unlink,
+    /// Moving a key between keyrings (`KEYCTL_MOVE`).
+    Move,
+    /// Searching a keyring (`KEYCTL_SEARCH`).
+    Search,
+    /// Reading the payload of a key (`KEYCTL_READ`).
+    Read,
+    /// Instantiating a key (`KEYCTL_INSTANTIATE`).
+    Instantiate,
+    /// Negatively instantiating a key (`KEYCTL_NEGATE`).
+    Negate,
+    /// Rejecting a key (`KEYCTL_REJECT`).
+    Reject,
+    /// Setting the default request keyring (`KEYCTL_SET_REQKEY_KEYRING`).
+    SetDefaultKeyring,
+    /// Setting the expiration of a key (`KEYCTL_SET_TIMEOUT`).
+    SetTimeout,
+    /// Assuming the authority to instantiate a key (`KEYCTL_ASSUME_AUTHORITY`).
+    AssumeAuthority,
+    /// Reading the security context of a key (`KEYCTL_GET_SECURITY`).
+    GetSecurity,
+    /// Invalidating a key (`KEYCTL_INVALIDATE`).
+    Invalidate,
+    /// Attaching a persistent keyring (`KEYCTL_GET_PERSISTENT`).
+    GetPersistent,
+    /// Computing a Diffie-Hellman value (`KEYCTL_DH_COMPUTE`).
+    DhCompute,
+    /// Restricting links into a keyring (`KEYCTL_RESTRICT_KEYRING`).
+    RestrictKeyring,
+    /// Querying public key support (`KEYCTL_PKEY_QUERY`).
+    PKeyQuery,
+    /// Encrypting data with a key (`KEYCTL_PKEY_ENCRYPT`).
+    PKeyEncrypt,
+    /// Decrypting data with a key (`KEYCTL_PKEY_DECRYPT`).
+    PKeyDecrypt,
+    /// Signing data with a key (`KEYCTL_PKEY_SIGN`).
+    PKeySign,
+    /// Verifying a signature with a key (`KEYCTL_PKEY_VERIFY`).
+    PKeyVerify,
+    /// Querying the capabilities of the kernel (`KEYCTL_CAPABILITIES`).
+    Capabilities,
+    /// Watching a key for changes (`KEYCTL_WATCH_KEY`).
+    WatchKey,
+    /// Setting up or reading from a notification queue.
+    NotificationQueue,
+    /// Reading the list of keys from `/proc/keys`.
+    ProcKeys,
+    /// Reading the key quotas from `/proc/key-users`.
+    ProcKeyUsers,
+    /// Accessing the keys subsystem tunables in `/proc/sys/kernel/keys`.
+    KeySysctl,
+    /// Allocating secret memory.
+    MemfdSecret,
+    /// Verifying the identity pinned to a handle.
+    VerifyIdentity,
+    /// Checking that a serial may refer to a key.
+    CheckSerial,
+    /// Renewing the timeout of a key in the background.
+    LeaseRenewal,
+}
+
+impl Operation {
+    /// The name of the kernel interface for the operation.
+    pub fn name(self) -> &'static str {
+        match self {
+            Operation::AddKey => "add_key",
+            Operation::RequestKey => "request_key",
+            Operation::GetKeyringId => "KEYCTL_GET_KEYRING_ID",
+            Operation::JoinSessionKeyring => "KEYCTL_JOIN_SESSION_KEYRING",
+            Operation::Update => "KEYCTL_UPDATE",
+            Operation::Revoke => "KEYCTL_REVOKE",
+            Operation::Chown => "KEYCTL_CHOWN",
+            Operation::SetPermissions => "KEYCTL_SETPERM",
+            Operation::Describe => "KEYCTL_DESCRIBE",
+            Operation::Clear => "KEYCTL_CLEAR",
+            Operation::Link => "KEYCTL_LINK",
+            Operation::Unlink => "KEYCTL_UNLINK",
+            Operation::Move => "KEYCTL_MOVE",
+            Operation::Search => "KEYCTL_SEARCH",
+            Operation::Read => "KEYCTL_READ",
+            Operation::Instantiate => "KEYCTL_INSTANTIATE",
+            Operation::Negate => "KEYCTL_NEGATE",
+            Operation::Reject => "KEYCTL_REJECT",
+            Operation::SetDefaultKeyring => "KEYCTL_SET_REQKEY_KEYRING",
+            Operation::SetTimeout => "KEYCTL_SET_TIMEOUT",
+            Operation::AssumeAuthority => "KEYCTL_ASSUME_AUTHORITY",
+            Operation::GetSecurity => "KEYCTL_GET_SECURITY",
+            Operation::Invalidate => "KEYCTL_INVALIDATE",
+            Operation::GetPersistent => "KEYCTL_GET_PERSISTENT",
+            Operation::DhCompute => "KEYCTL_DH_COMPUTE",
+            Operation::RestrictKeyring => "KEYCTL_RESTRICT_KEYRING",
+            Operation::PKeyQuery => "KEYCTL_PKEY_QUERY",
+            Operation::PKeyEncrypt => "KEYCTL_PKEY_ENCRYPT",
+            Operation::PKeyDecrypt => "KEYCTL_PKEY_DECRYPT",
+            Operation::PKeySign => "KEYCTL_PKEY_SIGN",
+            Operation::PKeyVerify => "KEYCTL_PKEY_VERIFY",
+            Operation::Capabilities => "KEYCTL_CAPABILITIES",
+            Operation::WatchKey => "KEYCTL_WATCH_KEY",
+            Operation::NotificationQueue => "notification queue",
+            Operation::ProcKeys => "/proc/keys",
+            Operation::ProcKeyUsers => "/proc/key-users",
+            Operation::KeySysctl => "/proc/sys/kernel/keys",
+            Operation::MemfdSecret => "memfd_secret",
+            Operation::VerifyIdentity => "identity verification",
+            Operation::CheckSerial => "serial validation",
+            Operation::LeaseRenewal => "key lease renewal",
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A classification of errors returned by the kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// #[non_exhaustive]
+pub enum ErrorKind {
+    /// The key or keyring does not exist or is not linked where expected.
+    NotFound,
+    /// The key has expired.
+    Expired,
+    /// The key has been revoked.
+    Revoked,
+    /// The key was rejected when it was instantiated.
+    Rejected,
+    /// The caller lacks the permissions required for the operation.
+    PermissionDenied,
+    /// The operation would exceed the user's key quota.
+    QuotaExceeded,
+    /// The key type is not available or does not support the operation.
+    TypeUnsupported,
+    /// A key or keyring with a matching description already exists.
+    AlreadyExists,
+    /// An argument was invalid (e.g., an invalid ID or a key used as a keyring).
+    InvalidInput,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorKind {
+    fn from_errno(errno: errno::Errno) -> Self {
+        match errno.0 {
+            libc::ENOKEY | libc::ENOENT => ErrorKind::NotFound,
+            libc::EKEYEXPIRED => ErrorKind::Expired,
+            libc::EKEYREVOKED => ErrorKind::Revoked,
+            libc::EKEYREJECTED => ErrorKind::Rejected,
+            libc::EACCES | libc::EPERM => ErrorKind::PermissionDenied,
+            libc::EDQUOT => ErrorKind::QuotaExceeded,
+            libc::ENODEV | libc::EOPNOTSUPP => ErrorKind::TypeUnsupported,
+            libc::EEXIST => ErrorKind::AlreadyExists,
+            libc::EINVAL | libc::E2BIG | libc::ENOTDIR | libc::EFAULT => ErrorKind::InvalidInput,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// An error from the keys subsystem.
+///
+/// In addition to the error code from the kernel, the operation being performed and, where
+/// known, the key and key type involved are recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    errno: errno::Errno,
+    operation: Operation,
+    serial: Option<KeySpec>,
+    key_type: Option<Cow<'static, str>>,
+    quota: Option<QuotaCheck>,
+}
+
+impl Error {
+    pub(crate) fn new(operation: Operation, errno: errno::Errno) -> Self {
+        Error {
+            errno,
+            operation,
+            serial: None,
+            key_type: None,
+            quota: None,
+        }
+    }
+
+    pub(crate) fn from_io(operation: Operation, err: &io::Error) -> Self {
+        Self::new(
+            operation,
+            errno::Errno(err.raw_os_error().unwrap_or(libc::EIO)),
+        )
+    }
+
+    pub(crate) fn with_serial<S>(mut self, serial: S) -> Self
+    where
+        S: Into<KeySpec>,
+    {
+        self.serial = Some(serial.into());
+        self
+    }
+
+    pub(crate) fn with_key_type<T>(mut self, key_type: T) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+    {
+        self.key_type = Some(key_type.into());
+        self
+    }
+
+    pub(crate) fn with_quota(mut self, quota: QuotaCheck) -> Self {
+        self.quota = Some(quota);
+        self
+    }
+
+    /// The classification of the error.
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::from_errno(self.errno)
+    }
+
+    /// The error code from the kernel.
+    pub fn errno(&self) -> errno::Errno {
+        self.errno
+    }
+
+    /// The operation which failed.
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// The key or keyring the operation was performed on, if known.
+    ///
+    /// Special keyrings have no serial; see `key_spec`.
+    pub fn serial(&self) -> Option<KeyringSerial> {
+        self.serial.and_then(KeySpec::serial)
+    }
+
+    /// The key or keyring the operation was performed on as passed to the kernel, if known.
+    pub fn key_spec(&self) -> Option<KeySpec> {
+        self.serial
+    }
+
+    /// The key type involved in the operation, if known.
+    pub fn key_type(&self) -> Option<&str> {
+        self.key_type.as_ref().map(AsRef::as_ref)
+    }
+
+    /// The quota check which failed, if any.
+    pub fn quota(&self) -> Option<&QuotaCheck> {
+        self.quota.as_ref()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} failed", self.operation)?;
+        if let Some(serial) = self.serial {
+            write!(f, " on {}", serial)?;
+        }
+        if let Some(key_type) = self.key_type.as_ref() {
+            write!(f, " (type `{}`)", key_type)?;
+        }
+        if let Some(quota) = self.quota.as_ref() {
+            write!(
+                f,
+                " (needs {} bytes; {}/{} keys and {}/{} bytes in use)",
+                quota.bytes_needed,
+                quota.quota.qnkeys,
+                quota.quota.maxkeys,
+                quota.quota.qnbytes,
+                quota.quota.maxbytes,
+            )?;
+        }
+        write!(f, ": {}", self.errno)
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<Error> for errno::Errno {
+    fn from(err: Error) -> Self {
+        err.errno
+    }
+}
+
+impl PartialEq<errno::Errno> for Error {
+    fn eq(&self, rhs: &errno::Errno) -> bool {
+        self.errno == *rhs
+    }
+}
+
+/// Simpler `Result` type with the error already set.
+pub type Result<T> = result::Result<T, Error>;
+
+/// Attach context to errors returned from the raw bindings.
+pub(crate) trait ErrnoContext<T> {
+    /// Record the operation which failed.
+    fn op(self, operation: Operation) -> Result<T>;
+
+    /// Record the operation which failed and the key it was performed on.
+    fn on<S>(self, operation: Operation, serial: S) -> Result<T>
+    where
+        S: Into<KeySpec>;
+}
+
+impl<T> ErrnoContext<T> for result::Result<T, errno::Errno> {
+    fn op(self, operation: Operation) -> Result<T> {
+        self.map_err(|errno| Error::new(operation, errno))
+    }
+
+    fn on<S>(self, operation: Operation, serial: S) -> Result<T>
+    where
+        S: Into<KeySpec>,
+    {
+        self.map_err(|errno| Error::new(operation, errno).with_serial(serial))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use keyutils_raw::KEY_SPEC_SESSION_KEYRING;
+
+    use super::{Error, ErrorKind, Operation};
+    use crate::{KeyQuota, KeySpec, KeyringSerial};
+
+    fn check(code: i32, kind: ErrorKind) {
+        let err = Error::new(Operation::Read, errno::Errno(code));
+        assert_eq!(err.kind(), kind);
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        check(libc::ENOKEY, ErrorKind::NotFound);
+        check(libc::ENOENT, ErrorKind::NotFound);
+        check(libc::EKEYEXPIRED, ErrorKind::Expired);
+        check(libc::EKEYREVOKED, ErrorKind::Revoked);
+        check(libc::EKEYREJECTED, ErrorKind::Rejected);
+        check(libc::EACCES, ErrorKind::PermissionDenied);
+        check(libc::EPERM, ErrorKind::PermissionDenied);
+        check(libc::EDQUOT, ErrorKind::QuotaExceeded);
+        check(libc::ENODEV, ErrorKind::TypeUnsupported);
+        check(libc::EOPNOTSUPP, ErrorKind::TypeUnsupported);
+        check(libc::EEXIST, ErrorKind::AlreadyExists);
+        check(libc::EINVAL, ErrorKind::InvalidInput);
+        check(libc::ENOTDIR, ErrorKind::InvalidInput);
+        check(libc::EDEADLK, ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_error_display() {
+        let errno = errno::Errno(libc::EACCES);
+        let err = Error::new(Operation::Search, errno);
+        assert_eq!(err.to_string(), format!("KEYCTL_SEARCH failed: {}", errno));
+
+        let err = err
+            .with_serial(KeyringSerial::new(1234).unwrap())
+            .with_key_type("user");
+        assert_eq!(
+            err.to_string(),
+            format!("KEYCTL_SEARCH failed on 1234 (type `user`): {}", errno),
+        );
+    }
+
+    #[test]
+    fn test_error_special_keyring() {
+        let errno = errno::Errno(libc::ENOKEY);
+        let err = Error::new(Operation::GetKeyringId, errno).with_serial(KEY_SPEC_SESSION_KEYRING);
+        assert_eq!(err.serial(), None);
+        assert_eq!(err.key_spec(), Some(KeySpec::SessionKeyring));
+        assert_eq!(
+            err.to_string(),
+            format!("KEYCTL_GET_KEYRING_ID failed on -3: {}", errno),
+        );
+
+        let serial = KeyringSerial::new(1234).unwrap();
+        let err = Error::new(Operation::Read, errno).with_serial(serial);
+        assert_eq!(err.serial(), Some(serial));
+        assert_eq!(err.key_spec(), Some(KeySpec::Serial(serial)));
+    }
+
+    #[test]
+    fn test_error_quota() {
+        let errno = errno::Errno(libc::EDQUOT);
+        let check = KeyQuota::parse(" 1000:     5 5/5 5/200 19980/20000")
+            .unwrap()
+            .check("description", &[0; 20]);
+        let err = Error::new(Operation::AddKey, errno).with_quota(check);
+        assert_eq!(err.quota(), Some(&check));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "add_key failed (needs 32 bytes; 5/200 keys and 19980/20000 bytes in use): {}",
+                errno,
+            ),
+        );
+    }
+
+    #[test]
+    fn test_error_into_errno() {
+        let errno = errno::Errno(libc::EKEYEXPIRED);
+        let err = Error::new(Operation::Read, errno);
+        assert_eq!(err, errno);
+        assert_eq!(errno::Errno::from(err), errno);
+    }
+}
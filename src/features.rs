@@ -0,0 +1,211 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::thread;
+
+use keyutils_raw::*;
+use uninit::out_ref::Out;
+
+use crate::error::{ErrnoContext, Operation, Result};
+use crate::keytypes;
+use crate::KeyType;
+
+/// Optional features of the keys subsystem supported by the running kernel.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KernelFeatures {
+    /// Whether the kernel reports its capabilities directly (`KEYCTL_CAPABILITIES`).
+    ///
+    /// If this is `false`, the other fields were determined by probing the kernel.
+    pub capabilities: bool,
+    /// Whether persistent keyrings are supported.
+    pub persistent_keyrings: bool,
+    /// Whether Diffie-Hellman computations are supported.
+    pub diffie_hellman: bool,
+    /// Whether public key operations (encrypt, decrypt, sign, and verify) are supported.
+    pub public_key: bool,
+    /// Whether the `big_key` key type is available.
+    pub big_key: bool,
+    /// Whether keys may be invalidated.
+    pub invalidate: bool,
+    /// Whether links into keyrings may be restricted.
+    pub restrict_keyring: bool,
+    /// Whether keys may be moved between keyrings.
+    pub move_key: bool,
+    /// Whether keys generate notifications which may be watched.
+    pub notifications: bool,
+    /// Whether keyring names are namespaced per user namespace.
+    pub namespaced_keyring_names: bool,
+    /// Whether key lookups may include a namespace tag.
+    pub namespaced_key_tags: bool,
+}
+
+/// The number of capability bytes understood by this crate.
+const CAPABILITY_BYTES: usize = 2;
+
+/// A key ID which never refers to a key.
+///
+/// Operations are probed by passing this ID and checking whether the kernel knows about the
+/// operation (it fails to look up the key) or not (`EOPNOTSUPP`). The kernel never assigns serial
+/// numbers below 3, so probing has no side effects.
+fn probe_serial() -> KeySpec {
+    KeySpec::Serial(KeyringSerial::new(1).expect("the probe ID is non-zero"))
+}
+
+fn is_supported<T>(res: std::result::Result<T, errno::Errno>) -> bool {
+    !matches!(res, Err(errno::Errno(libc::EOPNOTSUPP)))
+}
+
+impl KernelFeatures {
+    /// Determine the features supported by the running kernel.
+    ///
+    /// Kernels which support `KEYCTL_CAPABILITIES` (Linux 5.3 and newer) report their
+    /// capabilities directly. Older kernels are probed by making calls which fail without side
+    /// effects and inspecting the error codes returned.
+    pub fn probe() -> Result<Self> {
+        let mut caps = [0; CAPABILITY_BYTES];
+        match keyctl_capabilities(Some(Out::from(&mut caps[..]))) {
+            Ok(_) => Ok(Self::from_capabilities(caps)),
+            Err(errno::Errno(libc::EOPNOTSUPP)) => Ok(Self::from_probes()),
+            Err(err) => Err(err).op(Operation::Capabilities),
+        }
+    }
+
+    fn from_capabilities(caps: [u8; CAPABILITY_BYTES]) -> Self {
+        KernelFeatures {
+            capabilities: caps[0] & KEYCTL_CAPS0_CAPABILITIES != 0,
+            persistent_keyrings: caps[0] & KEYCTL_CAPS0_PERSISTENT_KEYRINGS != 0,
+            diffie_hellman: caps[0] & KEYCTL_CAPS0_DIFFIE_HELLMAN != 0,
+            public_key: caps[0] & KEYCTL_CAPS0_PUBLIC_KEY != 0,
+            big_key: caps[0] & KEYCTL_CAPS0_BIG_KEY != 0,
+            invalidate: caps[0] & KEYCTL_CAPS0_INVALIDATE != 0,
+            restrict_keyring: caps[0] & KEYCTL_CAPS0_RESTRICT_KEYRING != 0,
+            move_key: caps[0] & KEYCTL_CAPS0_MOVE != 0,
+            namespaced_keyring_names: caps[1] & KEYCTL_CAPS1_NS_KEYRING_NAME != 0,
+            namespaced_key_tags: caps[1] & KEYCTL_CAPS1_NS_KEY_TAG != 0,
+            notifications: caps[1] & KEYCTL_CAPS1_NOTIFICATIONS != 0,
+        }
+    }
+
+    fn from_probes() -> Self {
+        let probe = probe_serial();
+
+        KernelFeatures {
+            capabilities: false,
+            persistent_keyrings: is_supported(keyctl_get_persistent(!0, probe)),
+            diffie_hellman: is_supported(keyctl_dh_compute(probe, probe, probe, None)),
+            public_key: is_supported(keyctl_pkey_query(probe, "")),
+            big_key: Self::probe_big_key(),
+            invalidate: is_supported(keyctl_invalidate(probe)),
+            restrict_keyring: is_supported(keyctl_restrict_keyring(probe, Restriction::AllLinks)),
+            move_key: is_supported(keyctl_move(probe, probe, probe, 0)),
+            notifications: is_supported(keyctl_watch_key(probe, -1, None)),
+            // Namespaced keyrings were introduced alongside `KEYCTL_CAPABILITIES`.
+            namespaced_keyring_names: false,
+            namespaced_key_tags: false,
+        }
+    }
+
+    fn probe_big_key() -> bool {
+        // Probe from a short-lived thread so that the destination keyring is a throwaway thread
+        // keyring rather than one of the long-lived keyrings of the process or user.
+        thread::spawn(|| {
+            let keyring = match keyctl_get_keyring_id(KEY_SPEC_THREAD_KEYRING, true) {
+                Ok(keyring) => keyring,
+                Err(_) => return false,
+            };
+
+            // Unknown key types are rejected with `ENODEV` while an empty payload is rejected by
+            // the `big_key` type itself, so no key is ever created. Any other failure means that
+            // the type could not be checked at all.
+            let res = add_key(keytypes::BigKey::name(), "", &[], keyring.into());
+            matches!(
+                res,
+                Ok(_) | Err(errno::Errno(libc::EINVAL)) | Err(errno::Errno(libc::EDQUOT)),
+            )
+        })
+        .join()
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use keyutils_raw::*;
+
+    use super::KernelFeatures;
+
+    #[test]
+    fn test_from_capabilities() {
+        let features = KernelFeatures::from_capabilities([0, 0]);
+        assert_eq!(features, KernelFeatures::default());
+
+        let features = KernelFeatures::from_capabilities([!0, !0]);
+        assert!(features.capabilities);
+        assert!(features.persistent_keyrings);
+        assert!(features.diffie_hellman);
+        assert!(features.public_key);
+        assert!(features.big_key);
+        assert!(features.invalidate);
+        assert!(features.restrict_keyring);
+        assert!(features.move_key);
+        assert!(features.notifications);
+        assert!(features.namespaced_keyring_names);
+        assert!(features.namespaced_key_tags);
+
+        let features = KernelFeatures::from_capabilities([
+            KEYCTL_CAPS0_CAPABILITIES | KEYCTL_CAPS0_INVALIDATE | KEYCTL_CAPS0_MOVE,
+            KEYCTL_CAPS1_NOTIFICATIONS,
+        ]);
+        assert_eq!(
+            features,
+            KernelFeatures {
+                capabilities: true,
+                invalidate: true,
+                move_key: true,
+                notifications: true,
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_probes_match_capabilities() {
+        let features = KernelFeatures::probe().unwrap();
+        if !features.capabilities {
+            return;
+        }
+
+        let probed = KernelFeatures::from_probes();
+        assert_eq!(probed.persistent_keyrings, features.persistent_keyrings);
+        assert_eq!(probed.diffie_hellman, features.diffie_hellman);
+        assert_eq!(probed.public_key, features.public_key);
+        assert_eq!(probed.big_key, features.big_key);
+        assert_eq!(probed.invalidate, features.invalidate);
+        assert_eq!(probed.restrict_keyring, features.restrict_keyring);
+        assert_eq!(probed.move_key, features.move_key);
+        assert_eq!(probed.notifications, features.notifications);
+    }
+}
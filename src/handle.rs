@@ -0,0 +1,371 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::{Duration, SystemTime};
+
+use keyutils_raw::*;
+use uninit::extension_traits::AsOut;
+use uninit::out_ref::Out;
+
+use crate::api::Description;
+use crate::constants::{Permission, TimeoutRounding};
+use crate::error::{ErrnoContext, Error, Operation, Result};
+use crate::proc::{KeyFlags, KeyInfo, KeyTimeout};
+
+/// Fill a buffer with data returned by the kernel.
+///
+/// The existing capacity of the buffer is tried first and the buffer is only grown if the kernel
+/// reports that more space is needed. Returns the size reported by the kernel.
+pub(crate) fn kernel_buffer<F>(buffer: &mut Vec<u8>, mut fill: F) -> Result<usize>
+where
+    F: FnMut(Option<Out<[u8]>>) -> Result<usize>,
+{
+    buffer.clear();
+    loop {
+        let capacity = buffer.capacity();
+        buffer.resize(capacity, 0);
+        let write_buffer = if capacity == 0 {
+            // Just query the size.
+            None
+        } else {
+            Some(buffer.as_mut_slice().as_out())
+        };
+        let sz = fill(write_buffer)?;
+
+        // If we got everything, exit.
+        if sz <= capacity {
+            buffer.truncate(sz);
+            return Ok(sz);
+        }
+
+        // Resize for the additional capacity we need.
+        buffer.reserve(sz - capacity);
+    }
+}
+
+/// Convert a string returned by the kernel into a `String`.
+fn kernel_string(mut buffer: Vec<u8>, operation: Operation, id: KeyringSerial) -> Result<String> {
+    // Remove the trailing NUL the kernel adds.
+    buffer.pop();
+    // Descriptions are provided by userspace and need not be valid UTF-8.
+    String::from_utf8(buffer)
+        .map_err(|_| Error::new(operation, errno::Errno(libc::EINVAL)).with_serial(id))
+}
+
+fn description_raw(id: KeyringSerial) -> Result<String> {
+    let mut buffer = Vec::new();
+    kernel_buffer(&mut buffer, |write_buffer| {
+        keyctl_describe(id.into(), write_buffer).on(Operation::Describe, id)
+    })?;
+    kernel_string(buffer, Operation::Describe, id)
+}
+
+pub(crate) fn description_impl(id: KeyringSerial) -> Result<Description> {
+    description_raw(id).and_then(|desc| {
+        Description::parse(&desc).ok_or_else(|| {
+            Error::new(Operation::Describe, errno::Errno(libc::EINVAL)).with_serial(id)
+        })
+    })
+}
+
+/// An identity fingerprint of a key or keyring.
+///
+/// The kernel reuses the serial numbers of garbage collected keys, so a long-lived handle may
+/// end up referring to an unrelated key. The fingerprint records the properties of a key which
+/// are fixed when it is created (its type, description, and quota accounting) along with its
+/// owner so that such reuse may be detected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyIdentity {
+    type_: String,
+    description: String,
+    uid: libc::uid_t,
+    in_quota: Option<bool>,
+}
+
+impl KeyIdentity {
+    /// Capture the identity of a key or keyring.
+    ///
+    /// Uses `KEYCTL_DESCRIBE`, so `view` permission is required.
+    pub fn capture(id: KeyringSerial) -> Result<Self> {
+        let description = description_impl(id)?;
+        // `/proc/keys` is only used for information which is not verified later.
+        let in_quota = KeyInfo::for_serial(id)
+            .ok()
+            .map(|info| info.flags.contains(KeyFlags::IN_QUOTA));
+
+        Ok(KeyIdentity {
+            type_: description.type_,
+            description: description.description,
+            uid: description.uid,
+            in_quota,
+        })
+    }
+
+    /// Whether a description of a key matches the identity.
+    fn matches(&self, description: &Description) -> bool {
+        self.type_ == description.type_
+            && self.description == description.description
+            && self.uid == description.uid
+    }
+
+    /// Whether the quota accounting of a key matches the identity.
+    fn matches_quota(&self, id: KeyringSerial) -> Result<bool> {
+        Ok(match self.in_quota {
+            Some(in_quota) => {
+                KeyInfo::for_serial(id)?.flags.contains(KeyFlags::IN_QUOTA) == in_quota
+            },
+            None => true,
+        })
+    }
+
+    /// The type of the key.
+    pub fn type_(&self) -> &str {
+        &self.type_
+    }
+
+    /// The description of the key.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The user owner of the key.
+    pub fn uid(&self) -> libc::uid_t {
+        self.uid
+    }
+
+    /// Whether the key is counted against its owner's quota.
+    ///
+    /// This is read from `/proc/keys` when the identity is captured, if possible. If it is not
+    /// available, it is not verified.
+    pub fn in_quota(&self) -> Option<bool> {
+        self.in_quota
+    }
+}
+
+/// Operations shared by every handle to an object in the kernel's keyring subsystem.
+///
+/// This is implemented by `Key`, `Keyring`, and `TypedKey` so that code may be generic over
+/// keys and keyrings.
+pub trait KeyHandle {
+    /// The serial number of the key or keyring.
+    fn serial(&self) -> KeyringSerial;
+
+    /// The identity fingerprint pinned to the handle, if any.
+    fn identity(&self) -> Option<&KeyIdentity> {
+        None
+    }
+
+    /// Replace the identity fingerprint pinned to the handle.
+    ///
+    /// Handles which cannot store a fingerprint ignore this.
+    fn set_identity(&mut self, identity: Option<KeyIdentity>) {
+        let _ = identity;
+    }
+
+    /// Capture the identity of the key or keyring and pin it to the handle.
+    ///
+    /// Once pinned, operations which modify the key or keyring first verify that the serial
+    /// still refers to the same key using `verify_identity`. Note that this narrows, but does
+    /// not close, the window in which the serial may be reused.
+    fn pin_identity(&mut self) -> Result<()> {
+        let identity = KeyIdentity::capture(self.serial())?;
+        self.set_identity(Some(identity));
+        Ok(())
+    }
+
+    /// Verify that the handle still refers to the key or keyring it was pinned to.
+    ///
+    /// Fails with `ESTALE` if the serial now refers to a different key. Handles without a
+    /// pinned identity always pass.
+    fn verify_identity(&self) -> Result<()> {
+        let identity = if let Some(identity) = self.identity() {
+            identity
+        } else {
+            return Ok(());
+        };
+
+        let id = self.serial();
+        if identity.matches(&description_impl(id)?) && identity.matches_quota(id)? {
+            Ok(())
+        } else {
+            Err(Error::new(Operation::VerifyIdentity, errno::Errno(libc::ESTALE)).with_serial(id))
+        }
+    }
+
+    /// Revokes the key or keyring. Requires `write` permission.
+    fn revoke(self) -> Result<()>
+    where
+        Self: Sized,
+    {
+        self.verify_identity()?;
+        let id = self.serial();
+        keyctl_revoke(id.into()).on(Operation::Revoke, id)
+    }
+
+    /// Change the user which owns the key or keyring.
+    ///
+    /// Requires the `setattr` permission and the SysAdmin capability to change it to anything
+    /// other than the current user. Any pinned identity is updated to the new owner.
+    fn chown(&mut self, uid: libc::uid_t) -> Result<()> {
+        self.verify_identity()?;
+        let id = self.serial();
+        keyctl_chown(id.into(), Some(uid), None).on(Operation::Chown, id)?;
+        if let Some(identity) = self.identity() {
+            let identity = KeyIdentity {
+                uid,
+                ..identity.clone()
+            };
+            self.set_identity(Some(identity));
+        }
+        Ok(())
+    }
+
+    /// Change the group which owns the key or keyring.
+    ///
+    /// Requires the `setattr` permission and the SysAdmin capability to change it to anything
+    /// other than a group of which the current user is a member.
+    fn chgrp(&mut self, gid: libc::gid_t) -> Result<()> {
+        self.verify_identity()?;
+        let id = self.serial();
+        keyctl_chown(id.into(), None, Some(gid)).on(Operation::Chown, id)
+    }
+
+    /// Set the permissions on the key or keyring.
+    ///
+    /// Requires the `setattr` permission and the SysAdmin capability if the current user does
+    /// not own the key or keyring.
+    fn set_permissions(&mut self, perms: Permission) -> Result<()> {
+        self.verify_identity()?;
+        let id = self.serial();
+        keyctl_setperm(id.into(), perms.bits()).on(Operation::SetPermissions, id)
+    }
+
+    /// Retrieve metadata about the key or keyring.
+    ///
+    /// Malformed data from the kernel is reported as an `EINVAL` error.
+    fn description(&self) -> Result<Description> {
+        description_impl(self.serial())
+    }
+
+    /// Retrieve the raw description of the key or keyring into a caller-provided buffer.
+    ///
+    /// The description is formatted as `type;uid;gid;perm;description` and the size of the
+    /// description (including the trailing NUL) is returned. If the buffer is too small, its
+    /// contents are unspecified and the call should be retried with a buffer of the returned
+    /// size. Requires `view` permission.
+    fn describe_into(&self, buffer: &mut [u8]) -> Result<usize> {
+        let id = self.serial();
+        keyctl_describe(id.into(), Some(buffer.as_out())).on(Operation::Describe, id)
+    }
+
+    /// Retrieve the raw description of the key or keyring into a reusable buffer.
+    ///
+    /// The existing capacity of the buffer is used before growing it. The buffer holds the
+    /// description without the trailing NUL and its length is returned. See `describe_into`.
+    fn describe_into_vec(&self, buffer: &mut Vec<u8>) -> Result<usize> {
+        let id = self.serial();
+        kernel_buffer(buffer, |write_buffer| {
+            keyctl_describe(id.into(), write_buffer).on(Operation::Describe, id)
+        })?;
+        // Remove the trailing NUL the kernel adds.
+        buffer.pop();
+        Ok(buffer.len())
+    }
+
+    /// Set an expiration timer on the key or keyring to `timeout`.
+    ///
+    /// Any partial seconds are ignored, but a non-zero timeout always lasts at least a second.
+    /// A timeout of 0 means "no expiration". Requires the `setattr` permission.
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.set_timeout_rounded(timeout, TimeoutRounding::Down)
+    }
+
+    /// Set an expiration timer on the key or keyring, rounding `timeout` as requested.
+    ///
+    /// See `set_timeout`.
+    fn set_timeout_rounded(&mut self, timeout: Duration, rounding: TimeoutRounding) -> Result<()> {
+        self.verify_identity()?;
+        let id = self.serial();
+        keyctl_set_timeout(id.into(), rounding.seconds(timeout)).on(Operation::SetTimeout, id)
+    }
+
+    /// Set the key or keyring to expire at `expiry`.
+    ///
+    /// The kernel only supports timeouts relative to the current time in whole seconds, so the
+    /// key expires up to a second early. Times which have already passed cause the key to
+    /// expire within a second. Requires the `setattr` permission.
+    fn set_expiry(&mut self, expiry: SystemTime) -> Result<()> {
+        let timeout = expiry
+            .duration_since(SystemTime::now())
+            .unwrap_or_else(|_| Duration::from_secs(0));
+        // Never let an expiry turn into "no expiration".
+        let timeout = timeout.max(Duration::from_nanos(1));
+        self.set_timeout(timeout)
+    }
+
+    /// The time until the key or keyring expires according to `/proc/keys`.
+    ///
+    /// Returns `None` if the key does not expire and a zero duration if it has expired. The
+    /// kernel rounds the remaining time down to a unit which depends on how far away the
+    /// expiration is (see `KeyTimeout`). Requires `view` permission.
+    fn remaining_lifetime(&self) -> Result<Option<Duration>> {
+        Ok(match self.info()?.timeout {
+            KeyTimeout::Permanent => None,
+            KeyTimeout::Expired => Some(Duration::from_secs(0)),
+            KeyTimeout::Remaining(remaining) => Some(remaining),
+        })
+    }
+
+    /// The security context of the key or keyring. Depends on the security manager loaded into
+    /// the kernel (e.g., SELinux or AppArmor).
+    fn security(&self) -> Result<String> {
+        let id = self.serial();
+        let mut buffer = Vec::new();
+        kernel_buffer(&mut buffer, |write_buffer| {
+            keyctl_get_security(id.into(), write_buffer).on(Operation::GetSecurity, id)
+        })?;
+        kernel_string(buffer, Operation::GetSecurity, id)
+    }
+
+    /// Information about the key or keyring from `/proc/keys`.
+    ///
+    /// Fails with `ENOKEY` if the key is not visible to the current process (i.e., the `view`
+    /// permission is not granted).
+    fn info(&self) -> Result<KeyInfo> {
+        KeyInfo::for_serial(self.serial())
+    }
+
+    /// Invalidates the key or keyring and schedules it for removal. Requires the `search`
+    /// permission.
+    fn invalidate(self) -> Result<()>
+    where
+        Self: Sized,
+    {
+        self.verify_identity()?;
+        let id = self.serial();
+        keyctl_invalidate(id.into()).on(Operation::Invalidate, id)
+    }
+}
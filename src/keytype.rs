@@ -26,6 +26,8 @@
 
 use std::borrow::Cow;
 
+use crate::secret::WipedPayload;
+
 /// A trait for representing a type of key in the Linux keyring subsystem.
 pub trait KeyType {
     /// The type for describing the key.
@@ -35,62 +37,104 @@ pub trait KeyType {
 
     /// The name of the keytype.
     fn name() -> &'static str;
+
+    /// The maximum size of a payload accepted by the keytype.
+    ///
+    /// Payloads are checked against this before being handed to the kernel. `None` means that
+    /// only the kernel's global payload size limit applies.
+    fn max_payload_size() -> Option<usize> {
+        None
+    }
 }
 
 /// A description for a key.
 pub trait KeyDescription {
     /// The description of the key.
-    fn description(&self) -> Cow<str>;
+    fn description(&self) -> Cow<'_, str>;
 }
 
 impl KeyDescription for str {
-    fn description(&self) -> Cow<str> {
+    fn description(&self) -> Cow<'_, str> {
         Cow::Borrowed(self)
     }
 }
 
 impl KeyDescription for String {
-    fn description(&self) -> Cow<str> {
+    fn description(&self) -> Cow<'_, str> {
         Cow::Borrowed(self)
     }
 }
 
+/// A description which may be parsed from the description of a key in the kernel.
+pub trait FromKeyDescription: Sized {
+    /// Parse the description of a key.
+    ///
+    /// Returns `None` if the description is not valid for the key type.
+    fn from_description(description: &str) -> Option<Self>;
+}
+
+impl FromKeyDescription for String {
+    fn from_description(description: &str) -> Option<Self> {
+        Some(description.into())
+    }
+}
+
 /// A payload for a key.
 pub trait KeyPayload {
     /// The payload for the key.
-    fn payload(&self) -> Cow<[u8]>;
+    ///
+    /// Payloads of key types which hold secrets (e.g., `encrypted`, `trusted`, or `rxrpc`) may be
+    /// formatted into a new allocation. Nothing wipes the returned data, so callers which keep it
+    /// around must overwrite it themselves. Use `wiped_payload` to have this done on drop.
+    fn payload(&self) -> Cow<'_, [u8]>;
+
+    /// The payload for the key which is overwritten with zeros when dropped.
+    fn wiped_payload(&self) -> WipedPayload<'_> {
+        WipedPayload::new(self.payload())
+    }
 }
 
 impl KeyPayload for () {
-    fn payload(&self) -> Cow<[u8]> {
+    fn payload(&self) -> Cow<'_, [u8]> {
         Cow::Borrowed(&[])
     }
 }
 
 impl KeyPayload for str {
-    fn payload(&self) -> Cow<[u8]> {
+    fn payload(&self) -> Cow<'_, [u8]> {
         Cow::Borrowed(self.as_bytes())
     }
 }
 
 impl KeyPayload for String {
-    fn payload(&self) -> Cow<[u8]> {
+    fn payload(&self) -> Cow<'_, [u8]> {
         Cow::Borrowed(self.as_bytes())
     }
 }
 
 impl KeyPayload for [u8] {
-    fn payload(&self) -> Cow<[u8]> {
+    fn payload(&self) -> Cow<'_, [u8]> {
         Cow::Borrowed(self)
     }
 }
 
 impl KeyPayload for Vec<u8> {
-    fn payload(&self) -> Cow<[u8]> {
+    fn payload(&self) -> Cow<'_, [u8]> {
         Cow::Borrowed(self)
     }
 }
 
+/// A key type whose payload may be decoded after reading it from the kernel.
+pub trait ReadableKeyType: KeyType {
+    /// The type for representing the decoded payload of the key.
+    type Contents;
+
+    /// Decode a payload read from the kernel.
+    ///
+    /// Returns `None` if the payload is malformed.
+    fn decode(payload: Vec<u8>) -> Option<Self::Contents>;
+}
+
 /// A key which may be restricted into being added to a keyring.
 pub trait RestrictableKeyType: KeyType {
     /// The type for representing a restriction for adding keys of this type.
@@ -100,17 +144,17 @@ pub trait RestrictableKeyType: KeyType {
 /// A restriction for a key.
 pub trait KeyRestriction {
     /// The restriction string of the key.
-    fn restriction(&self) -> Cow<str>;
+    fn restriction(&self) -> Cow<'_, str>;
 }
 
 impl KeyRestriction for str {
-    fn restriction(&self) -> Cow<str> {
+    fn restriction(&self) -> Cow<'_, str> {
         Cow::Borrowed(self)
     }
 }
 
 impl KeyRestriction for String {
-    fn restriction(&self) -> Cow<str> {
+    fn restriction(&self) -> Cow<'_, str> {
         Cow::Borrowed(self)
     }
 }
@@ -58,6 +58,17 @@ impl KeyType for Asymmetric {
     }
 }
 
+impl ReadableKeyType for Asymmetric {
+    /// The raw payload of the key.
+    ///
+    /// Note that the kernel does not currently support reading asymmetric keys.
+    type Contents = Vec<u8>;
+
+    fn decode(payload: Vec<u8>) -> Option<Self::Contents> {
+        Some(payload)
+    }
+}
+
 /// A restriction that may be placed onto a keyring using an asymmetric key.
 #[derive(Debug, Clone, PartialEq, Eq)]
 // #[non_exhaustive]
@@ -95,7 +106,7 @@ impl AsymmetricRestriction {
 }
 
 impl KeyRestriction for AsymmetricRestriction {
-    fn restriction(&self) -> Cow<str> {
+    fn restriction(&self) -> Cow<'_, str> {
         match self {
             AsymmetricRestriction::BuiltinTrusted => "builtin_trusted".into(),
             AsymmetricRestriction::BuiltinAndSecondaryTrusted => {
@@ -46,3 +46,12 @@ impl KeyType for BigKey {
         KEY_TYPE_BIG_KEY
     }
 }
+
+impl ReadableKeyType for BigKey {
+    /// Big keys read back as the payload they were given.
+    type Contents = Vec<u8>;
+
+    fn decode(payload: Vec<u8>) -> Option<Self::Contents> {
+        Some(payload)
+    }
+}
@@ -28,7 +28,7 @@
 
 use std::borrow::Cow;
 
-use super::ByteBuf;
+use super::{from_hex, ByteBuf};
 use crate::keytype::*;
 
 /// Blacklist hashes.
@@ -42,7 +42,7 @@ impl KeyType for Blacklist {
     type Payload = ();
 
     fn name() -> &'static str {
-        "keyring"
+        "blacklist"
     }
 }
 
@@ -64,6 +64,14 @@ impl HashType {
             HashType::Other(ref s) => s,
         }
     }
+
+    /// The hash type for a name.
+    fn from_name(name: &str) -> Self {
+        match name {
+            "tbs" => HashType::Tbs,
+            other => HashType::Other(other.to_owned().into()),
+        }
+    }
 }
 
 impl PartialEq for HashType {
@@ -73,6 +81,7 @@ impl PartialEq for HashType {
 }
 
 /// The description of a blacklist key.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Description {
     /// The hash type to blacklist.
     pub hash_type: HashType,
@@ -81,7 +90,20 @@ pub struct Description {
 }
 
 impl KeyDescription for Description {
-    fn description(&self) -> Cow<str> {
+    fn description(&self) -> Cow<'_, str> {
         format!("{}:{:x}", self.hash_type.name(), ByteBuf(&self.hash)).into()
     }
 }
+
+impl FromKeyDescription for Description {
+    fn from_description(description: &str) -> Option<Self> {
+        let (hash_type, hash) = description.split_once(':')?;
+        let hash_type = HashType::from_name(hash_type);
+        let hash = from_hex(hash)?;
+
+        Some(Description {
+            hash_type,
+            hash,
+        })
+    }
+}
@@ -43,6 +43,30 @@ impl KeyType for DnsResolver {
     }
 }
 
+impl ReadableKeyType for DnsResolver {
+    /// The comma-separated records found by the query (e.g., addresses).
+    ///
+    /// Binary server list payloads are not supported.
+    type Contents = Vec<String>;
+
+    fn decode(payload: Vec<u8>) -> Option<Self::Contents> {
+        // Binary payloads start with a NUL byte.
+        if payload.first() == Some(&0) {
+            return None;
+        }
+
+        let payload = String::from_utf8(payload).ok()?;
+        Some(
+            payload
+                .trim_end_matches('\0')
+                .split(',')
+                .filter(|record| !record.is_empty())
+                .map(Into::into)
+                .collect(),
+        )
+    }
+}
+
 /// The DNS record to lookup.
 #[derive(Debug, Clone, Eq)]
 // #[non_exhaustive]
@@ -67,6 +91,16 @@ impl QueryType {
             QueryType::Other(s) => s,
         }
     }
+
+    /// The query type for a DNS record name.
+    fn from_name(name: &str) -> Self {
+        match name {
+            "a" => QueryType::A,
+            "aaaa" => QueryType::AAAA,
+            "afsdb" => QueryType::AFSDB,
+            other => QueryType::Other(other.to_owned().into()),
+        }
+    }
 }
 
 impl PartialEq for QueryType {
@@ -87,10 +121,41 @@ pub struct Description {
 }
 
 impl KeyDescription for Description {
-    fn description(&self) -> Cow<str> {
+    fn description(&self) -> Cow<'_, str> {
         match &self.query_type {
             Some(ref query_type) => format!("{}:{}", query_type.name(), self.name).into(),
             _ => self.name.clone(),
         }
     }
 }
+
+impl FromKeyDescription for Description {
+    fn from_description(description: &str) -> Option<Self> {
+        let (query_type, name) = match description.split_once(':') {
+            Some((query_type, name)) => (Some(QueryType::from_name(query_type)), name),
+            None => (None, description),
+        };
+
+        Some(Description {
+            query_type,
+            name: name.to_owned().into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keytype::*;
+
+    use super::DnsResolver;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            DnsResolver::decode(b"192.0.2.1,2001:db8::1".to_vec()),
+            Some(vec!["192.0.2.1".into(), "2001:db8::1".into()]),
+        );
+        assert_eq!(DnsResolver::decode(b"".to_vec()), Some(vec![]));
+        assert_eq!(DnsResolver::decode(b"\0\x01".to_vec()), None);
+    }
+}
@@ -27,9 +27,11 @@
 //! Encrypted keys
 
 use std::borrow::Cow;
+use std::fmt;
 
-use super::ByteBuf;
+use super::{format_payload, from_hex, ByteBuf};
 use crate::keytype::*;
+use crate::secret::{zeroize, Redacted};
 
 /// Encrypted keys.
 ///
@@ -48,6 +50,36 @@ impl KeyType for Encrypted {
     fn name() -> &'static str {
         "encrypted"
     }
+
+    fn max_payload_size() -> Option<usize> {
+        // Limit enforced by `encrypted_preparse` in the kernel.
+        Some(32767)
+    }
+}
+
+impl ReadableKeyType for Encrypted {
+    type Contents = Blob;
+
+    fn decode(payload: Vec<u8>) -> Option<Self::Contents> {
+        let payload = String::from_utf8(payload).ok()?;
+        let mut fields = payload.trim_end_matches('\0').split(' ');
+        let format = Format::from_name(fields.next()?)?;
+        let (keytype, description) = fields.next()?.split_once(':')?;
+        let keytype = MasterKeyType::from_name(keytype)?;
+        let keylen = fields.next()?.parse().ok()?;
+        let data = from_hex(fields.next()?)?;
+        if fields.next().is_some() {
+            return None;
+        }
+
+        Some(Blob {
+            format,
+            keytype,
+            description: description.to_owned().into(),
+            keylen,
+            data,
+        })
+    }
 }
 
 /// The format of the encrypted payload.
@@ -77,6 +109,16 @@ impl Format {
             Format::Enc32 => "enc32",
         }
     }
+
+    /// The key format for a name.
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Format::Default),
+            "ecryptfs" => Some(Format::Ecryptfs),
+            "enc32" => Some(Format::Enc32),
+            _ => None,
+        }
+    }
 }
 
 // XXX(rust-1.57): use `#[default]`
@@ -111,10 +153,36 @@ impl MasterKeyType {
             MasterKeyType::User => "user",
         }
     }
+
+    /// The master key type for a name.
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "trusted" => Some(MasterKeyType::Trusted),
+            "user" => Some(MasterKeyType::User),
+            _ => None,
+        }
+    }
 }
 
-/// The payload for an encrypted key.
+/// The contents of an encrypted key as read from the kernel.
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blob {
+    /// The format of the key.
+    pub format: Format,
+    /// The type of key used to encrypt the key.
+    pub keytype: MasterKeyType,
+    /// The description of the key used to encrypt the key.
+    pub description: Cow<'static, str>,
+    /// The size of the decrypted key.
+    pub keylen: usize,
+    /// The encrypted key data, including its IV and HMAC.
+    pub data: Vec<u8>,
+}
+
+/// The payload for an encrypted key.
+///
+/// Blobs are redacted from `Debug` output and wiped when dropped.
+#[derive(Clone, PartialEq, Eq)]
 // #[non_exhaustive]
 pub enum Payload {
     /// Create a new key.
@@ -151,8 +219,51 @@ pub enum Payload {
     },
 }
 
+impl fmt::Debug for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Payload::New {
+                format,
+                keytype,
+                description,
+                keylen,
+            } => {
+                f.debug_struct("New")
+                    .field("format", format)
+                    .field("keytype", keytype)
+                    .field("description", description)
+                    .field("keylen", keylen)
+                    .finish()
+            },
+            Payload::Load {
+                ..
+            } => f.debug_struct("Load").field("blob", &Redacted).finish(),
+            Payload::Update {
+                keytype,
+                description,
+            } => {
+                f.debug_struct("Update")
+                    .field("keytype", keytype)
+                    .field("description", description)
+                    .finish()
+            },
+        }
+    }
+}
+
+impl Drop for Payload {
+    fn drop(&mut self) {
+        if let Payload::Load {
+            blob,
+        } = self
+        {
+            zeroize(blob);
+        }
+    }
+}
+
 impl KeyPayload for Payload {
-    fn payload(&self) -> Cow<[u8]> {
+    fn payload(&self) -> Cow<'_, [u8]> {
         match self {
             Payload::New {
                 format,
@@ -160,23 +271,70 @@ impl KeyPayload for Payload {
                 description,
                 keylen,
             } => {
-                format!(
+                format_payload(format_args!(
                     "new {} {}:{} {}",
                     format.unwrap_or_default().name(),
                     keytype.name(),
                     description,
                     keylen,
-                )
+                ))
             },
             Payload::Load {
                 blob,
-            } => format!("load {:x}", ByteBuf(blob)),
+            } => format_payload(format_args!("load {:x}", ByteBuf(blob))),
             Payload::Update {
                 keytype,
                 description,
-            } => format!("update {}:{}", keytype.name(), description),
+            } => format_payload(format_args!("update {}:{}", keytype.name(), description)),
         }
-        .into_bytes()
-        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keytype::*;
+
+    use super::{Blob, Encrypted, Format, MasterKeyType, Payload};
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            Encrypted::decode(b"default user:kmk 32 deadbeef".to_vec()),
+            Some(Blob {
+                format: Format::Default,
+                keytype: MasterKeyType::User,
+                description: "kmk".into(),
+                keylen: 32,
+                data: vec![222, 173, 190, 239],
+            }),
+        );
+    }
+
+    #[test]
+    fn test_decode_malformed() {
+        assert_eq!(Encrypted::decode(b"default user:kmk 32".to_vec()), None);
+        assert_eq!(Encrypted::decode(b"other user:kmk 32 00".to_vec()), None);
+        assert_eq!(Encrypted::decode(b"default kmk 32 00".to_vec()), None);
+        assert_eq!(Encrypted::decode(b"default user:kmk 32 0".to_vec()), None);
+        assert_eq!(
+            Encrypted::decode(b"default user:kmk 32 00 00".to_vec()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_payload() {
+        let payload = Payload::New {
+            format: None,
+            keytype: MasterKeyType::User,
+            description: "kmk".into(),
+            keylen: 32,
+        };
+        assert_eq!(payload.payload(), &b"new default user:kmk 32"[..]);
+
+        let payload = Payload::Load {
+            blob: vec![222, 173, 190, 239],
+        };
+        assert_eq!(payload.payload(), &b"load deadbeef"[..]);
     }
 }
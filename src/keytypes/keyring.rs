@@ -26,8 +26,13 @@
 
 //! Keyrings
 
+use std::convert::TryInto;
+use std::mem;
+
 use crate::keytype::*;
+use crate::KeyringSerial;
 use keyutils_raw::KEY_TYPE_KEYRING;
+use log::error;
 
 /// Keyrings contain other keys.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -43,3 +48,69 @@ impl KeyType for Keyring {
         KEY_TYPE_KEYRING
     }
 }
+
+impl ReadableKeyType for Keyring {
+    /// Keyrings read as the serials of their immediate children.
+    type Contents = Vec<KeyringSerial>;
+
+    fn decode(payload: Vec<u8>) -> Option<Self::Contents> {
+        let chunks = payload.chunks_exact(mem::size_of::<KeyringSerial>());
+        if !chunks.remainder().is_empty() {
+            error!(
+                "A keyring did not have the right number of bytes for its child key and keyring \
+                 IDs: {}",
+                payload.len(),
+            );
+            return None;
+        }
+
+        chunks
+            .map(|chunk| {
+                let id = i32::from_ne_bytes(chunk.try_into().ok()?);
+                if id <= 0 {
+                    error!(
+                        "A keyring had a non-positive child key or keyring ID: {}",
+                        id
+                    );
+                    return None;
+                }
+                KeyringSerial::new(id)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keytype::*;
+    use crate::KeyringSerial;
+
+    use super::Keyring;
+
+    #[test]
+    fn test_decode() {
+        let payload = [1234_i32, 5678]
+            .iter()
+            .flat_map(|id| id.to_ne_bytes().to_vec())
+            .collect();
+        assert_eq!(
+            Keyring::decode(payload),
+            Some(vec![
+                KeyringSerial::new(1234).unwrap(),
+                KeyringSerial::new(5678).unwrap(),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_decode_malformed() {
+        assert_eq!(Keyring::decode(vec![0; 3]), None);
+        assert_eq!(Keyring::decode(vec![0; 4]), None);
+
+        let payload = [1234_i32, -5]
+            .iter()
+            .flat_map(|id| id.to_ne_bytes().to_vec())
+            .collect();
+        assert_eq!(Keyring::decode(payload), None);
+    }
+}
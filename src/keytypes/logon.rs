@@ -47,6 +47,11 @@ impl KeyType for Logon {
     fn name() -> &'static str {
         KEY_TYPE_LOGON
     }
+
+    fn max_payload_size() -> Option<usize> {
+        // Limit enforced by `user_preparse` in the kernel, shared with `user` keys.
+        Some(32767)
+    }
 }
 
 /// The description of a logon key.
@@ -59,7 +64,18 @@ pub struct Description {
 }
 
 impl KeyDescription for Description {
-    fn description(&self) -> Cow<str> {
+    fn description(&self) -> Cow<'_, str> {
         format!("{}:{}", self.subtype, self.description).into()
     }
 }
+
+impl FromKeyDescription for Description {
+    fn from_description(description: &str) -> Option<Self> {
+        let (subtype, description) = description.split_once(':')?;
+
+        Some(Description {
+            subtype: subtype.to_owned().into(),
+            description: description.to_owned().into(),
+        })
+    }
+}
@@ -29,6 +29,7 @@
 //! The Linux kernel supports many types of keys. They may be compiled out or available as
 //! modules. The types provided here try to make it easier to use these keys.
 
+use std::borrow::Cow;
 use std::fmt;
 
 pub mod asymmetric;
@@ -64,6 +65,29 @@ pub use self::trusted::Trusted;
 pub mod user;
 pub use self::user::User;
 
+/// Format a payload into a buffer of exactly the right size.
+///
+/// Growing a buffer while formatting would leave partial copies of the payload in freed memory
+/// which is never wiped.
+fn format_payload(args: fmt::Arguments) -> Cow<'static, [u8]> {
+    struct Counter(usize);
+
+    impl fmt::Write for Counter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 += s.len();
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    let mut payload = String::new();
+    // Formatting into these writers cannot fail.
+    let _ = fmt::write(&mut counter, args);
+    payload.reserve_exact(counter.0);
+    let _ = fmt::write(&mut payload, args);
+    payload.into_bytes().into()
+}
+
 /// A structure for assisting in display binary data.
 struct ByteBuf<'a>(&'a [u8]);
 
@@ -76,9 +100,23 @@ impl fmt::LowerHex for ByteBuf<'_> {
     }
 }
 
+/// Decode a string of hexadecimal digits.
+// XXX(rust-1.87): use `usize::is_multiple_of`
+#[allow(clippy::manual_is_multiple_of)]
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16).ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::ByteBuf;
+    use super::{format_payload, from_hex, ByteBuf};
 
     fn check(input: &[u8], expected: &str) {
         assert_eq!(format!("{:x}", ByteBuf(input)), expected);
@@ -90,4 +128,23 @@ mod tests {
         check(&[0, 1], "0001");
         check(&[222, 173, 190, 239], "deadbeef");
     }
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(from_hex(""), Some(vec![]));
+        assert_eq!(from_hex("00"), Some(vec![0]));
+        assert_eq!(from_hex("DEADbeef"), Some(vec![222, 173, 190, 239]));
+        assert_eq!(from_hex("0"), None);
+        assert_eq!(from_hex("0g"), None);
+        assert_eq!(from_hex("+1"), None);
+        assert_eq!(from_hex("é0"), None);
+    }
+
+    #[test]
+    fn test_format_payload() {
+        let payload = format_payload(format_args!("load {:x}", ByteBuf(&[222, 173])));
+        assert_eq!(payload, &b"load dead"[..]);
+        let payload = payload.into_owned();
+        assert_eq!(payload.capacity(), payload.len());
+    }
 }
@@ -27,8 +27,10 @@
 //! Keys for RxRPC clients.
 
 use std::borrow::Cow;
+use std::fmt;
 
 use crate::keytype::*;
+use crate::secret::{zeroize, Redacted};
 
 /// An RxRPC client key.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -45,7 +47,9 @@ impl KeyType for RxRPC {
 }
 
 /// The payload for RxRPC client keys.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// The session key and ticket are redacted from `Debug` output and wiped when dropped.
+#[derive(Clone, PartialEq, Eq)]
 pub struct Payload {
     expiry: u32,
     version: u8,
@@ -53,9 +57,43 @@ pub struct Payload {
     ticket: Vec<u8>,
 }
 
+impl fmt::Debug for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Payload")
+            .field("expiry", &self.expiry)
+            .field("version", &self.version)
+            .field("session_key", &Redacted)
+            .field("ticket", &Redacted)
+            .finish()
+    }
+}
+
+impl Drop for Payload {
+    fn drop(&mut self) {
+        zeroize(&mut self.session_key);
+        zeroize(&mut self.ticket);
+    }
+}
+
+impl Payload {
+    /// Create a payload for an RxRPC client key.
+    pub fn new(expiry: u32, version: u8, session_key: [u8; 8], ticket: Vec<u8>) -> Self {
+        Payload {
+            expiry,
+            version,
+            session_key,
+            ticket,
+        }
+    }
+}
+
+/// The size of `struct rxrpc_key_sec2_v1` without its ticket.
+const SEC2_V1_HEADER_SIZE: usize = 20;
+
 impl KeyPayload for Payload {
-    fn payload(&self) -> Cow<[u8]> {
-        let mut payload = Vec::new();
+    fn payload(&self) -> Cow<'_, [u8]> {
+        // Allocate the full size up front so that no partial copies of the key are left behind.
+        let mut payload = Vec::with_capacity(SEC2_V1_HEADER_SIZE + self.ticket.len());
 
         // struct rxrpc_key_sec2_v1 {
         //     uint16_t    security_index; /* 2 */
@@ -78,3 +116,28 @@ impl KeyPayload for Payload {
         payload.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::keytype::*;
+
+    use super::{Payload, SEC2_V1_HEADER_SIZE};
+
+    fn payload() -> Payload {
+        Payload {
+            expiry: 1,
+            version: 2,
+            session_key: [0xab; 8],
+            ticket: vec![0xcd; 4],
+        }
+    }
+
+    #[test]
+    fn test_payload() {
+        let payload = payload().payload().into_owned();
+        assert_eq!(payload.len(), SEC2_V1_HEADER_SIZE + 4);
+        assert_eq!(payload.capacity(), payload.len());
+        assert_eq!(payload[12..20], [0xab; 8]);
+        assert_eq!(payload[20..], [0xcd; 4]);
+    }
+}
@@ -27,8 +27,10 @@
 //! RxRPC server keys
 
 use std::borrow::Cow;
+use std::fmt;
 
 use crate::keytype::*;
+use crate::secret::{zeroize, Redacted};
 
 /// An RxRPC server key.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -53,19 +55,62 @@ pub struct Description {
 }
 
 impl KeyDescription for Description {
-    fn description(&self) -> Cow<str> {
+    fn description(&self) -> Cow<'_, str> {
         format!("{}:{}", self.service_id, self.security_index).into()
     }
 }
 
+impl FromKeyDescription for Description {
+    fn from_description(description: &str) -> Option<Self> {
+        let (service_id, security_index) = description.split_once(':')?;
+        let service_id = service_id.parse().ok()?;
+        let security_index = security_index.parse().ok()?;
+
+        Some(Description {
+            service_id,
+            security_index,
+        })
+    }
+}
+
 /// The payload for an RxRPC server key.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// The key is redacted from `Debug` output and wiped when dropped, so the payload is no longer
+/// `Copy`.
+#[derive(Clone, PartialEq, Eq)]
 pub struct Payload {
     key: [u8; 8],
 }
 
+impl Payload {
+    /// Create a payload for an RxRPC server key.
+    pub fn new(key: [u8; 8]) -> Self {
+        Payload {
+            key,
+        }
+    }
+}
+
+impl From<[u8; 8]> for Payload {
+    fn from(key: [u8; 8]) -> Self {
+        Payload::new(key)
+    }
+}
+
+impl fmt::Debug for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Payload").field("key", &Redacted).finish()
+    }
+}
+
+impl Drop for Payload {
+    fn drop(&mut self) {
+        zeroize(&mut self.key);
+    }
+}
+
 impl KeyPayload for Payload {
-    fn payload(&self) -> Cow<[u8]> {
+    fn payload(&self) -> Cow<'_, [u8]> {
         Cow::Borrowed(&self.key)
     }
 }
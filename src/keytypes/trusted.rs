@@ -28,9 +28,11 @@
 
 use std::borrow::Cow;
 use std::fmt;
+use std::str;
 
-use super::ByteBuf;
+use super::{format_payload, from_hex, ByteBuf};
 use crate::keytype::*;
+use crate::secret::{zeroize, Redacted};
 
 /// Trusted keys are rooted in the TPM.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -44,6 +46,22 @@ impl KeyType for Trusted {
     fn name() -> &'static str {
         "trusted"
     }
+
+    fn max_payload_size() -> Option<usize> {
+        // Limit enforced by `trusted_preparse` in the kernel.
+        Some(32767)
+    }
+}
+
+impl ReadableKeyType for Trusted {
+    /// Trusted keys read as their sealed blob.
+    ///
+    /// The blob may be loaded back into the TPM using `Payload::Load`.
+    type Contents = Vec<u8>;
+
+    fn decode(payload: Vec<u8>) -> Option<Self::Contents> {
+        from_hex(str::from_utf8(&payload).ok()?)
+    }
 }
 
 /// Hashes supported by TPM devices.
@@ -82,15 +100,55 @@ impl TpmHash {
     }
 }
 
+/// Authorization data for a TPM.
+///
+/// The data is redacted from `Debug` output and wiped when dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TpmAuth([u8; 20]);
+
+impl TpmAuth {
+    /// Create authorization data.
+    pub fn new(auth: [u8; 20]) -> Self {
+        TpmAuth(auth)
+    }
+
+    /// The authorization data.
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl From<[u8; 20]> for TpmAuth {
+    fn from(auth: [u8; 20]) -> Self {
+        TpmAuth(auth)
+    }
+}
+
+impl fmt::Debug for TpmAuth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("TpmAuth").field(&Redacted).finish()
+    }
+}
+
+impl Drop for TpmAuth {
+    fn drop(&mut self) {
+        zeroize(&mut self.0);
+    }
+}
+
 /// Options for trusted keys.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct TrustedOptions {
     /// The ID of the sealing key to use.
     pub keyhandle: Option<u32>,
     /// The authorization for sealing keys.
-    pub keyauth: Option<[u8; 20]>,
+    ///
+    /// Raw authorization data may be converted using `From<[u8; 20]>`.
+    pub keyauth: Option<TpmAuth>,
     /// The authorization for sealing data.
-    pub blobauth: Option<[u8; 20]>,
+    ///
+    /// Raw authorization data may be converted using `From<[u8; 20]>`.
+    pub blobauth: Option<TpmAuth>,
     /// Platform Configuration Register (PCR) data.
     pub pcrinfo: Option<Vec<u8>>,
     /// The PCR number in the TPM to extend and lock the key.
@@ -121,12 +179,12 @@ impl fmt::Display for TrustedOptions {
         if let Some(keyauth) = self.keyauth.as_ref() {
             // keyauth=      ascii hex auth for sealing key; default 00...
             //               (40 ascii zeros)
-            write!(f, " keyauth={:x}", ByteBuf(keyauth))?;
+            write!(f, " keyauth={:x}", ByteBuf(keyauth.as_bytes()))?;
         }
         if let Some(blobauth) = self.blobauth.as_ref() {
             // blobauth=     ascii hex auth for sealed data; default 00...
             //               (40 ascii zeros)
-            write!(f, " blobauth={:x}", ByteBuf(blobauth))?;
+            write!(f, " blobauth={:x}", ByteBuf(blobauth.as_bytes()))?;
         }
         if let Some(pcrinfo) = self.pcrinfo.as_ref() {
             // pcrinfo=      ascii hex of PCR_INFO or PCR_INFO_LONG (no default)
@@ -164,7 +222,9 @@ impl fmt::Display for TrustedOptions {
 }
 
 /// The payload for trusted keys.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Blobs are redacted from `Debug` output and wiped when dropped.
+#[derive(Clone, PartialEq, Eq)]
 // #[non_exhaustive]
 pub enum Payload {
     /// Create a new key.
@@ -194,22 +254,91 @@ pub enum Payload {
     },
 }
 
+impl fmt::Debug for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Payload::New {
+                keylen,
+                options,
+            } => {
+                f.debug_struct("New")
+                    .field("keylen", keylen)
+                    .field("options", options)
+                    .finish()
+            },
+            Payload::Load {
+                options, ..
+            } => {
+                f.debug_struct("Load")
+                    .field("blob", &Redacted)
+                    .field("options", options)
+                    .finish()
+            },
+            Payload::Update {
+                options,
+            } => f.debug_struct("Update").field("options", options).finish(),
+        }
+    }
+}
+
+impl Drop for Payload {
+    fn drop(&mut self) {
+        if let Payload::Load {
+            blob, ..
+        } = self
+        {
+            zeroize(blob);
+        }
+    }
+}
+
 impl KeyPayload for Payload {
-    fn payload(&self) -> Cow<[u8]> {
+    fn payload(&self) -> Cow<'_, [u8]> {
         match self {
             Payload::New {
                 keylen,
                 options,
-            } => format!("new {}{}", keylen, options),
+            } => format_payload(format_args!("new {}{}", keylen, options)),
             Payload::Load {
                 blob,
                 options,
-            } => format!("load {:x}{}", ByteBuf(blob), options),
+            } => format_payload(format_args!("load {:x}{}", ByteBuf(blob), options)),
             Payload::Update {
                 options,
-            } => format!("update{}", options),
+            } => format_payload(format_args!("update{}", options)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keytype::*;
+
+    use super::{Payload, TpmAuth, TrustedOptions};
+
+    fn options() -> TrustedOptions {
+        TrustedOptions {
+            keyhandle: Some(0x8100_0001),
+            keyauth: Some(TpmAuth::new([0xab; 20])),
+            blobauth: Some([0xcd; 20].into()),
+            ..Default::default()
         }
-        .into_bytes()
-        .into()
+    }
+
+    #[test]
+    fn test_payload() {
+        let payload = Payload::Load {
+            blob: vec![222, 173, 190, 239],
+            options: options(),
+        };
+        assert_eq!(
+            payload.payload(),
+            format!(
+                "load deadbeef keyhandle=81000001 keyauth={} blobauth={}",
+                "ab".repeat(20),
+                "cd".repeat(20),
+            )
+            .as_bytes(),
+        );
     }
 }
@@ -44,4 +44,18 @@ impl KeyType for User {
     fn name() -> &'static str {
         KEY_TYPE_USER
     }
+
+    fn max_payload_size() -> Option<usize> {
+        // Limit enforced by `user_preparse` in the kernel.
+        Some(32767)
+    }
+}
+
+impl ReadableKeyType for User {
+    /// User keys read back as the payload they were given.
+    type Contents = Vec<u8>;
+
+    fn decode(payload: Vec<u8>) -> Option<Self::Contents> {
+        Some(payload)
+    }
 }
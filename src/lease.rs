@@ -0,0 +1,153 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::constants::TimeoutRounding;
+use crate::error::{Error, Operation, Result};
+use crate::handle::KeyHandle;
+
+/// The time a key is guaranteed to live once its timeout has been set.
+///
+/// Timeouts are rounded up to whole seconds, but the kernel measures expiration in whole seconds
+/// of wall-clock time, so a key may still expire up to a second early.
+fn guaranteed_lifetime(timeout: Duration) -> Duration {
+    let seconds = TimeoutRounding::Up.seconds(timeout);
+    Duration::from_secs(seconds.into())
+        .checked_sub(Duration::from_secs(1))
+        .unwrap_or_else(|| Duration::from_secs(0))
+}
+
+/// Clears a flag when the renewal thread exits, even if it panics.
+struct Running(Arc<AtomicBool>);
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Keeps a key or keyring alive by periodically extending its timeout.
+///
+/// The timeout is reset from a background thread for as long as the lease exists. Once the
+/// lease is dropped, the key expires after the timeout last set. Renewal stops at the first
+/// failure (e.g., if the key is revoked); the error is reported by `stop`.
+///
+/// Note that the renewal thread does not possess keys which are only reachable through the
+/// calling thread's keyring, so the key must grant `setattr` without possession in that case.
+#[derive(Debug)]
+pub struct KeyLease {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<Result<()>>>,
+    // XXX(rust-1.61): use `JoinHandle::is_finished`
+    running: Arc<AtomicBool>,
+}
+
+impl KeyLease {
+    /// Set the timeout of `handle` and renew it regularly.
+    ///
+    /// The timeout is renewed every half of the time the key is guaranteed to live (see
+    /// `with_interval`). Fails with `EINVAL` if `timeout` is not longer than a second.
+    pub fn new<H>(handle: H, timeout: Duration) -> Result<Self>
+    where
+        H: KeyHandle + Send + 'static,
+    {
+        Self::with_interval(handle, timeout, guaranteed_lifetime(timeout) / 2)
+    }
+
+    /// Set the timeout of `handle` and renew it every `interval`.
+    ///
+    /// The timeout is rounded up to whole seconds. Since the kernel may expire the key up to a
+    /// second early, this fails with `EINVAL` if `interval` is zero or not shorter than the
+    /// rounded timeout less a second. Requires the `setattr` permission.
+    pub fn with_interval<H>(mut handle: H, timeout: Duration, interval: Duration) -> Result<Self>
+    where
+        H: KeyHandle + Send + 'static,
+    {
+        if interval == Duration::from_secs(0) || interval >= guaranteed_lifetime(timeout) {
+            return Err(
+                Error::new(Operation::SetTimeout, errno::Errno(libc::EINVAL))
+                    .with_serial(handle.serial()),
+            );
+        }
+
+        handle.set_timeout_rounded(timeout, TimeoutRounding::Up)?;
+
+        let (stop, stopped) = mpsc::channel();
+        let running = Arc::new(AtomicBool::new(true));
+        let guard = Running(Arc::clone(&running));
+        let thread = thread::spawn(move || {
+            let _guard = guard;
+            loop {
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => {
+                        handle.set_timeout_rounded(timeout, TimeoutRounding::Up)?
+                    },
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => return Ok(()),
+                }
+            }
+        });
+
+        Ok(KeyLease {
+            stop: Some(stop),
+            thread: Some(thread),
+            running,
+        })
+    }
+
+    /// Whether the timeout is still being renewed.
+    pub fn is_active(&self) -> bool {
+        self.thread.is_some() && self.running.load(Ordering::SeqCst)
+    }
+
+    /// Stop renewing the timeout.
+    ///
+    /// Returns the error which stopped renewal early, if any. A panic while renewing is reported
+    /// as `EIO`.
+    pub fn stop(mut self) -> Result<()> {
+        self.stop_impl()
+    }
+
+    fn stop_impl(&mut self) -> Result<()> {
+        // Dropping the sender wakes up the renewal thread.
+        self.stop.take();
+        self.thread.take().map_or(Ok(()), |thread| {
+            thread.join().unwrap_or_else(|_| {
+                Err(Error::new(Operation::LeaseRenewal, errno::Errno(libc::EIO)))
+            })
+        })
+    }
+}
+
+impl Drop for KeyLease {
+    fn drop(&mut self) {
+        let _ = self.stop_impl();
+    }
+}
@@ -32,16 +32,38 @@
 #![allow(clippy::upper_case_acronyms)]
 
 mod api;
+mod builder;
 mod constants;
+mod error;
+mod features;
+mod handle;
 mod keytype;
+mod lease;
+mod proc;
+mod secret;
+mod specifier;
+mod sysctl;
+mod walk;
+mod watch;
 
 pub mod keytypes;
 
 pub use self::api::*;
+pub use self::builder::*;
 pub use self::constants::*;
+pub use self::error::*;
+pub use self::features::*;
+pub use self::handle::*;
 pub use self::keytype::*;
+pub use self::lease::*;
+pub use self::proc::*;
+pub use self::secret::*;
+pub use self::specifier::*;
+pub use self::sysctl::*;
+pub use self::walk::*;
+pub use self::watch::*;
 
-pub use keyutils_raw::{DefaultKeyring, KeyPermissions, KeyringSerial, TimeoutSeconds};
+pub use keyutils_raw::{DefaultKeyring, KeyPermissions, KeySpec, KeyringSerial, TimeoutSeconds};
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,697 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fs;
+use std::time::Duration;
+
+use bitflags::bitflags;
+use keyutils_raw::{KeyPermissions, KeyringSerial};
+use log::{error, warn};
+
+use crate::api::Description;
+use crate::constants::Permission;
+use crate::error::{Error, Operation, Result};
+use crate::handle::description_impl;
+use crate::sysctl::KeySysctl;
+
+const PROC_KEYS: &str = "/proc/keys";
+const PROC_KEY_USERS: &str = "/proc/key-users";
+
+/// The width of the key type column in `/proc/keys`.
+const TYPE_COLUMN_WIDTH: usize = 9;
+
+bitflags! {
+    /// State flags of a key as reported in `/proc/keys`.
+    pub struct KeyFlags: u8 {
+        /// The key has been instantiated.
+        const INSTANTIATED          = 0x01;
+        /// The key has been revoked.
+        const REVOKED               = 0x02;
+        /// The key is dead (its type has been unregistered).
+        const DEAD                  = 0x04;
+        /// The key contributes to the user's quota.
+        const IN_QUOTA              = 0x08;
+        /// The key is under construction by a userspace callback.
+        const UNDER_CONSTRUCTION    = 0x10;
+        /// The key is negatively instantiated.
+        const NEGATIVE              = 0x20;
+        /// The key has been invalidated.
+        const INVALIDATED           = 0x40;
+    }
+}
+
+/// The flag characters in the order in which `/proc/keys` displays them.
+const FLAG_CHARS: [(char, KeyFlags); 7] = [
+    ('I', KeyFlags::INSTANTIATED),
+    ('R', KeyFlags::REVOKED),
+    ('D', KeyFlags::DEAD),
+    ('Q', KeyFlags::IN_QUOTA),
+    ('U', KeyFlags::UNDER_CONSTRUCTION),
+    ('N', KeyFlags::NEGATIVE),
+    ('i', KeyFlags::INVALIDATED),
+];
+
+/// The expiration state of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyTimeout {
+    /// The key does not expire.
+    Permanent,
+    /// The key has expired.
+    Expired,
+    /// The key expires after (approximately) the given duration.
+    ///
+    /// The kernel rounds the remaining time down to seconds, minutes, hours, days, or weeks
+    /// depending on how far away the expiration is.
+    Remaining(Duration),
+}
+
+impl KeyTimeout {
+    fn parse(timeout: &str) -> Option<Self> {
+        match timeout {
+            "perm" => Some(KeyTimeout::Permanent),
+            "expd" => Some(KeyTimeout::Expired),
+            _ => {
+                let unit = match timeout.chars().last()? {
+                    's' => 1,
+                    'm' => 60,
+                    'h' => 60 * 60,
+                    'd' => 60 * 60 * 24,
+                    'w' => 60 * 60 * 24 * 7,
+                    _ => return None,
+                };
+                let count: u64 = timeout[..timeout.len() - 1].parse().ok()?;
+                Some(KeyTimeout::Remaining(Duration::from_secs(
+                    count.checked_mul(unit)?,
+                )))
+            },
+        }
+    }
+}
+
+/// Information about a key from `/proc/keys`.
+///
+/// Only keys which the calling process may view are listed.
+///
+/// The kernel does not escape descriptions in `/proc/keys`, so any user may add a key whose
+/// description contains text which looks like the entries of other keys. Entries read using
+/// `KeyInfo::all` may therefore be forged. Use `KeyInfo::for_serial` (or `KeyHandle::info`) for
+/// information about a specific key as it checks the entry against the kernel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyInfo {
+    /// The serial number of the key.
+    pub serial: KeyringSerial,
+    /// The state of the key.
+    pub flags: KeyFlags,
+    /// The number of references to the key.
+    pub usage: u32,
+    /// When the key expires.
+    pub timeout: KeyTimeout,
+    /// The permissions of the key.
+    pub perms: Permission,
+    /// The user owner of the key.
+    pub uid: libc::uid_t,
+    /// The group owner of the key.
+    pub gid: libc::gid_t,
+    /// The type of the key.
+    ///
+    /// Note that the kernel truncates type names to 9 characters.
+    pub type_: String,
+    /// The description of the key.
+    ///
+    /// Descriptions may contain any text, including newlines and `": "`, so this field may not
+    /// be trusted for entries which have not been checked using `KEYCTL_DESCRIBE`.
+    pub description: String,
+    /// A type-specific summary of the key (e.g., the size of the payload or the number of keys
+    /// in a keyring).
+    ///
+    /// The summary is split from the description at the last `": "`, so descriptions containing
+    /// `": "` on keys without a summary (such as negative keys) may be misinterpreted unless the
+    /// entry was read using `KeyInfo::for_serial`.
+    pub summary: Option<String>,
+}
+
+/// Split the next whitespace-delimited field from a line.
+fn next_field(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start_matches(' ');
+    let end = line.find(' ').unwrap_or(line.len());
+    if end == 0 {
+        None
+    } else {
+        Some(line.split_at(end))
+    }
+}
+
+impl KeyInfo {
+    /// Parse a line from `/proc/keys`.
+    ///
+    /// Returns `None` if the line is malformed.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (serial, rest) = next_field(line)?;
+        let serial = KeyringSerial::new(u32::from_str_radix(serial, 16).ok()? as i32)?;
+
+        let (flag_chars, rest) = next_field(rest)?;
+        if flag_chars.chars().count() != FLAG_CHARS.len() {
+            return None;
+        }
+        let flags = flag_chars.chars().zip(FLAG_CHARS.iter()).try_fold(
+            KeyFlags::empty(),
+            |flags, (c, &(expected, flag))| {
+                match c {
+                    '-' => Some(flags),
+                    c if c == expected => Some(flags | flag),
+                    _ => None,
+                }
+            },
+        )?;
+
+        let (usage, rest) = next_field(rest)?;
+        let usage = usage.parse().ok()?;
+        let (timeout, rest) = next_field(rest)?;
+        let timeout = KeyTimeout::parse(timeout)?;
+        let (perms, rest) = next_field(rest)?;
+        let bits = KeyPermissions::from_str_radix(perms, 16).ok()?;
+        if Permission::from_bits(bits).is_none() {
+            error!(
+                "New permission bits detected! Please report this upstream to \
+                 https://github.com/mathstuf/rust-keyutils: {}",
+                bits,
+            );
+        }
+        // The kernel "munges" unmapped IDs, but they are printed as signed values.
+        let (uid, rest) = next_field(rest)?;
+        let uid = uid.parse::<i64>().ok()? as libc::uid_t;
+        let (gid, rest) = next_field(rest)?;
+        let gid = gid.parse::<i64>().ok()? as libc::gid_t;
+
+        // The type is in a fixed-width column followed by a space.
+        let rest = rest.strip_prefix(' ')?;
+        let type_ = rest.get(..TYPE_COLUMN_WIDTH)?.trim_end_matches(' ');
+        if type_.is_empty() {
+            return None;
+        }
+        let rest = rest.get(TYPE_COLUMN_WIDTH..)?.strip_prefix(' ')?;
+
+        let (description, summary) = match rest.rsplit_once(": ") {
+            Some((description, summary)) => (description, Some(summary.into())),
+            None => (rest, None),
+        };
+
+        Some(KeyInfo {
+            serial,
+            flags,
+            usage,
+            timeout,
+            perms: Permission::from_bits_truncate(bits),
+            uid,
+            gid,
+            type_: type_.into(),
+            description: description.into(),
+            summary,
+        })
+    }
+
+    /// Read information about all keys visible to the current process.
+    ///
+    /// Lines which cannot be parsed are skipped since they may come from descriptions containing
+    /// newlines. Descriptions need not be valid UTF-8, so invalid sequences are replaced with
+    /// `U+FFFD`. The entries are not checked against the kernel, so they may be forged (see the
+    /// type documentation).
+    ///
+    /// Requires the kernel to have been built with `CONFIG_KEYS` and `/proc` to be mounted.
+    pub fn all() -> Result<Vec<Self>> {
+        let keys = fs::read(PROC_KEYS).map_err(|err| Error::from_io(Operation::ProcKeys, &err))?;
+
+        Ok(keys
+            .split(|&c| c == b'\n')
+            .filter(|line| !line.is_empty())
+            .filter_map(|line| {
+                let line = String::from_utf8_lossy(line);
+                let info = Self::parse(&line);
+                if info.is_none() {
+                    warn!("skipping unrecognized line in /proc/keys: {:?}", line);
+                }
+                info
+            })
+            .collect())
+    }
+
+    /// Read information about a single key.
+    ///
+    /// Entries for the key are checked against `KEYCTL_DESCRIBE` and the description and summary
+    /// are split using the description reported by the kernel. Expired and revoked keys may no
+    /// longer be described, so their entries are only checked for conflicts.
+    ///
+    /// Fails with `ENOKEY` if the key does not exist or is not visible to the current process
+    /// and `EINVAL` if `/proc/keys` contains conflicting entries for the key.
+    pub fn for_serial(serial: KeyringSerial) -> Result<Self> {
+        let description = match description_impl(serial) {
+            Ok(description) => Some(description),
+            Err(err)
+                if err == errno::Errno(libc::EKEYEXPIRED)
+                    || err == errno::Errno(libc::EKEYREVOKED) =>
+            {
+                None
+            },
+            Err(_) => {
+                return Err(
+                    Error::new(Operation::ProcKeys, errno::Errno(libc::ENOKEY)).with_serial(serial)
+                );
+            },
+        };
+        let mut entries = Self::all()?
+            .into_iter()
+            .filter(|info| info.serial == serial)
+            .filter_map(|info| {
+                match &description {
+                    Some(description) => info.confirm(description),
+                    None => Some(info),
+                }
+            });
+
+        let info = entries.next().ok_or_else(|| {
+            Error::new(Operation::ProcKeys, errno::Errno(libc::ENOKEY)).with_serial(serial)
+        })?;
+        // A forged entry which matches the kernel's description of the key may still disagree
+        // on the other fields. There is no way to tell which one is real.
+        if entries.any(|other| other != info) {
+            return Err(
+                Error::new(Operation::ProcKeys, errno::Errno(libc::EINVAL)).with_serial(serial)
+            );
+        }
+
+        Ok(info)
+    }
+
+    /// Check the entry against the kernel's description of the key.
+    ///
+    /// Returns the entry with the description and summary split using the real description.
+    fn confirm(mut self, description: &Description) -> Option<Self> {
+        if self.uid != description.uid
+            || self.gid != description.gid
+            || self.perms != description.perms
+            || !self.may_match(&description.type_, &description.description)
+        {
+            return None;
+        }
+
+        let full = match self.summary.take() {
+            Some(summary) => format!("{}: {}", self.description, summary),
+            None => self.description,
+        };
+        self.summary = full[description.description.len()..]
+            .strip_prefix(": ")
+            .map(Into::into);
+        self.description = description.description.clone();
+
+        Some(self)
+    }
+
+    /// Whether the entry may be for a key with the given type and description.
+    ///
+    /// Since the type may be truncated and the description may be split incorrectly, matches
+    /// should be confirmed by describing the key.
+    pub(crate) fn may_match(&self, type_: &str, description: &str) -> bool {
+        let type_ = type_.get(..TYPE_COLUMN_WIDTH).unwrap_or(type_);
+        if self.type_ != type_ {
+            return false;
+        }
+
+        match (
+            description.strip_prefix(self.description.as_str()),
+            &self.summary,
+        ) {
+            (Some(""), _) => true,
+            (Some(rest), Some(summary)) => rest.strip_prefix(": ") == Some(summary.as_str()),
+            _ => false,
+        }
+    }
+}
+
+/// Key usage and quota information for a user from `/proc/key-users`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyQuota {
+    /// The user the information is for.
+    pub uid: libc::uid_t,
+    /// The number of references to the kernel's record of the user.
+    pub usage: usize,
+    /// The number of keys owned by the user.
+    pub nkeys: usize,
+    /// The number of instantiated keys owned by the user.
+    pub nikeys: usize,
+    /// The number of keys counted against the user's quota.
+    pub qnkeys: usize,
+    /// The maximum number of keys the user may own.
+    pub maxkeys: usize,
+    /// The number of bytes counted against the user's quota.
+    pub qnbytes: usize,
+    /// The maximum number of bytes the user may use for keys.
+    pub maxbytes: usize,
+}
+
+/// Parse a `used/max` pair.
+fn parse_ratio(field: &str) -> Option<(usize, usize)> {
+    let (used, max) = field.split_once('/')?;
+    Some((used.parse().ok()?, max.parse().ok()?))
+}
+
+impl KeyQuota {
+    /// Parse a line from `/proc/key-users`.
+    ///
+    /// Returns `None` if the line is malformed.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (uid, rest) = next_field(line)?;
+        let uid = uid.strip_suffix(':')?.parse().ok()?;
+        let (usage, rest) = next_field(rest)?;
+        let usage = usage.parse().ok()?;
+        let (keys, rest) = next_field(rest)?;
+        let (nkeys, nikeys) = parse_ratio(keys)?;
+        let (quota_keys, rest) = next_field(rest)?;
+        let (qnkeys, maxkeys) = parse_ratio(quota_keys)?;
+        let (quota_bytes, rest) = next_field(rest)?;
+        let (qnbytes, maxbytes) = parse_ratio(quota_bytes)?;
+        if next_field(rest).is_some() {
+            return None;
+        }
+
+        Some(KeyQuota {
+            uid,
+            usage,
+            nkeys,
+            nikeys,
+            qnkeys,
+            maxkeys,
+            qnbytes,
+            maxbytes,
+        })
+    }
+
+    /// Read the quota information of all users with keys.
+    pub fn all() -> Result<Vec<Self>> {
+        let users = fs::read_to_string(PROC_KEY_USERS)
+            .map_err(|err| Error::from_io(Operation::ProcKeyUsers, &err))?;
+
+        users
+            .lines()
+            .map(|line| {
+                Self::parse(line).ok_or_else(|| {
+                    error!(
+                        "New /proc/key-users format detected! Please report this upstream to \
+                         https://github.com/mathstuf/rust-keyutils: {}",
+                        line,
+                    );
+                    Error::new(Operation::ProcKeyUsers, errno::Errno(libc::EINVAL))
+                })
+            })
+            .collect()
+    }
+
+    /// Read the quota information of a user.
+    ///
+    /// The kernel only lists users which own keys. Other users have no usage and the limits
+    /// are read from the tunables of the keys subsystem (see `KeySysctl`).
+    pub fn for_uid(uid: libc::uid_t) -> Result<Self> {
+        if let Some(quota) = Self::all()?.into_iter().find(|quota| quota.uid == uid) {
+            return Ok(quota);
+        }
+
+        let (maxkeys, maxbytes) = KeySysctl::read_quota_limits(uid)?;
+        Ok(KeyQuota {
+            uid,
+            usage: 0,
+            nkeys: 0,
+            nikeys: 0,
+            qnkeys: 0,
+            maxkeys,
+            qnbytes: 0,
+            maxbytes,
+        })
+    }
+
+    /// Read the quota information of the current user.
+    pub fn current() -> Result<Self> {
+        Self::for_uid(unsafe { libc::geteuid() })
+    }
+
+    /// The number of keys the user may still create.
+    pub fn keys_remaining(&self) -> usize {
+        self.maxkeys.saturating_sub(self.qnkeys)
+    }
+
+    /// The number of bytes the user may still use.
+    pub fn bytes_remaining(&self) -> usize {
+        self.maxbytes.saturating_sub(self.qnbytes)
+    }
+
+    /// Estimate whether a new key would fit within the quota.
+    pub fn check(&self, description: &str, payload: &[u8]) -> QuotaCheck {
+        QuotaCheck {
+            quota: *self,
+            bytes_needed: estimated_key_size(description, payload),
+        }
+    }
+}
+
+/// Estimate the number of bytes a key counts against the owner's quota.
+///
+/// The kernel charges the length of the description (with its NUL terminator) and the size of
+/// the payload as reported by the key type. Most key types report the size of the payload
+/// given to them.
+fn estimated_key_size(description: &str, payload: &[u8]) -> usize {
+    description.len() + 1 + payload.len()
+}
+
+/// The result of checking whether a new key would fit within a user's quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaCheck {
+    /// The quota of the user at the time of the check.
+    pub quota: KeyQuota,
+    /// The estimated number of bytes the new key would use.
+    pub bytes_needed: usize,
+}
+
+impl QuotaCheck {
+    /// Whether the key is expected to fit within the quota.
+    pub fn fits(&self) -> bool {
+        self.quota.keys_remaining() > 0 && self.bytes_needed <= self.quota.bytes_remaining()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use keyutils_raw::KeyringSerial;
+
+    use crate::api::Description;
+    use crate::constants::Permission;
+
+    use super::{KeyFlags, KeyInfo, KeyQuota, KeyTimeout};
+
+    #[test]
+    fn test_parse_keyring() {
+        let info = KeyInfo::parse(
+            "19bcae81 I------     1 perm 1f0b0000     0     0 keyring   .builtin_trusted_keys: \
+             empty",
+        )
+        .unwrap();
+        assert_eq!(info.serial, KeyringSerial::new(0x19bc_ae81).unwrap());
+        assert_eq!(info.flags, KeyFlags::INSTANTIATED);
+        assert_eq!(info.usage, 1);
+        assert_eq!(info.timeout, KeyTimeout::Permanent);
+        assert_eq!(info.perms.bits(), 0x1f0b_0000);
+        assert_eq!(info.uid, 0);
+        assert_eq!(info.gid, 0);
+        assert_eq!(info.type_, "keyring");
+        assert_eq!(info.description, ".builtin_trusted_keys");
+        assert_eq!(info.summary.as_deref(), Some("empty"));
+    }
+
+    #[test]
+    fn test_parse_key() {
+        let info =
+            KeyInfo::parse("807b6b3d I--Q---     2   5m 3f010000  1000  1000 user      a: b; c: 7")
+                .unwrap();
+        assert_eq!(
+            info.serial,
+            KeyringSerial::new(0x807b_6b3d_u32 as i32).unwrap()
+        );
+        assert_eq!(info.flags, KeyFlags::INSTANTIATED | KeyFlags::IN_QUOTA);
+        assert_eq!(info.usage, 2);
+        assert_eq!(
+            info.timeout,
+            KeyTimeout::Remaining(Duration::from_secs(300)),
+        );
+        assert_eq!(info.uid, 1000);
+        assert_eq!(info.gid, 1000);
+        assert_eq!(info.type_, "user");
+        assert_eq!(info.description, "a: b; c");
+        assert_eq!(info.summary.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn test_parse_truncated_type() {
+        let info = KeyInfo::parse(
+            "0a0b0c0d -----Ni     1 expd 3f010000     0 65534 dns_resol example.com",
+        )
+        .unwrap();
+        assert_eq!(info.flags, KeyFlags::NEGATIVE | KeyFlags::INVALIDATED);
+        assert_eq!(info.timeout, KeyTimeout::Expired);
+        assert_eq!(info.gid, 65534);
+        assert_eq!(info.type_, "dns_resol");
+        assert_eq!(info.description, "example.com");
+        assert_eq!(info.summary, None);
+    }
+
+    #[test]
+    fn test_may_match() {
+        let info =
+            KeyInfo::parse("807b6b3d I--Q---     2   5m 3f010000  1000  1000 user      a: b; c: 7")
+                .unwrap();
+        assert!(info.may_match("user", "a: b; c"));
+        assert!(!info.may_match("user", "a: b"));
+        assert!(!info.may_match("logon", "a: b; c"));
+
+        // A negative key does not have a summary, but its description is split anyway.
+        let info = KeyInfo::parse(
+            "0a0b0c0d I----N-     1 perm 3f010000     0     0 dns_resol a: example.com",
+        )
+        .unwrap();
+        assert!(info.may_match("dns_resolver", "a: example.com"));
+        // The split is ambiguous, so this must be confirmed by describing the key.
+        assert!(info.may_match("dns_resolver", "a"));
+        assert!(!info.may_match("dns_resolver", "example.com"));
+    }
+
+    #[test]
+    fn test_confirm() {
+        let description = |type_: &str, desc: &str| {
+            Description {
+                type_: type_.into(),
+                uid: 1000,
+                gid: 1000,
+                perms: Permission::from_bits_truncate(0x3f01_0000),
+                description: desc.into(),
+            }
+        };
+        let info =
+            KeyInfo::parse("807b6b3d I--Q---     2   5m 3f010000  1000  1000 user      a: b; c: 7")
+                .unwrap();
+
+        let confirmed = info
+            .clone()
+            .confirm(&description("user", "a: b; c"))
+            .unwrap();
+        assert_eq!(confirmed.description, "a: b; c");
+        assert_eq!(confirmed.summary.as_deref(), Some("7"));
+        let confirmed = info
+            .clone()
+            .confirm(&description("user", "a: b; c: 7"))
+            .unwrap();
+        assert_eq!(confirmed.description, "a: b; c: 7");
+        assert_eq!(confirmed.summary, None);
+
+        assert_eq!(info.clone().confirm(&description("logon", "a: b; c")), None);
+        assert_eq!(info.clone().confirm(&description("user", "a: b")), None);
+        let mut other_owner = description("user", "a: b; c");
+        other_owner.uid = 0;
+        assert_eq!(info.confirm(&other_owner), None);
+    }
+
+    #[test]
+    fn test_parse_timeouts() {
+        assert_eq!(
+            KeyTimeout::parse("59s"),
+            Some(KeyTimeout::Remaining(Duration::from_secs(59))),
+        );
+        assert_eq!(
+            KeyTimeout::parse("2h"),
+            Some(KeyTimeout::Remaining(Duration::from_secs(7200))),
+        );
+        assert_eq!(
+            KeyTimeout::parse("1w"),
+            Some(KeyTimeout::Remaining(Duration::from_secs(604_800))),
+        );
+        assert_eq!(KeyTimeout::parse("5y"), None);
+        assert_eq!(KeyTimeout::parse("s"), None);
+        assert_eq!(KeyTimeout::parse(""), None);
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert_eq!(KeyInfo::parse(""), None);
+        assert_eq!(
+            KeyInfo::parse("00000000 I------ 1 perm 3f010000 0 0 user x"),
+            None
+        );
+        assert_eq!(
+            KeyInfo::parse("0000000a X------     1 perm 3f010000     0     0 user      x"),
+            None,
+        );
+        assert_eq!(
+            KeyInfo::parse("0000000a I------     1 perm 3f010000     0     0 user"),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_parse_key_users() {
+        let quota = KeyQuota::parse("    0:     9 8/8 3/1000000 40/25000000").unwrap();
+        assert_eq!(
+            quota,
+            KeyQuota {
+                uid: 0,
+                usage: 9,
+                nkeys: 8,
+                nikeys: 8,
+                qnkeys: 3,
+                maxkeys: 1_000_000,
+                qnbytes: 40,
+                maxbytes: 25_000_000,
+            },
+        );
+        assert_eq!(quota.keys_remaining(), 999_997);
+        assert_eq!(quota.bytes_remaining(), 24_999_960);
+    }
+
+    #[test]
+    fn test_parse_key_users_malformed() {
+        assert_eq!(KeyQuota::parse(""), None);
+        assert_eq!(KeyQuota::parse("    0     9 8/8 3/200 40/20000"), None);
+        assert_eq!(KeyQuota::parse("    0:     9 8/8 3/200 40"), None);
+        assert_eq!(KeyQuota::parse("    0:     9 8/8 3/200 40/20000 1/2"), None);
+    }
+
+    #[test]
+    fn test_quota_check() {
+        let quota = KeyQuota::parse(" 1000:     5 5/5 5/200 19980/20000").unwrap();
+        assert!(quota.check("key", &[0; 16]).fits());
+        assert!(!quota.check("key", &[0; 17]).fits());
+        assert_eq!(quota.check("key", &[0; 17]).bytes_needed, 21);
+
+        let quota = KeyQuota::parse(" 1000:     5 5/5 200/200 0/20000").unwrap();
+        assert!(!quota.check("key", &[]).fits());
+    }
+}
@@ -0,0 +1,456 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::borrow::{Borrow, BorrowMut, Cow};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+use std::slice;
+use std::sync::atomic::{self, Ordering};
+
+use crate::error::{Error, Operation, Result};
+use crate::keytype::KeyPayload;
+
+/// The syscall number of `memfd_secret`.
+///
+/// Syscalls added since Linux 5.1 share their number across architectures.
+#[cfg(any(
+    all(target_arch = "x86_64", target_pointer_width = "64"),
+    target_arch = "aarch64",
+    target_arch = "riscv64",
+    target_arch = "s390x",
+))]
+const SYS_MEMFD_SECRET: Option<libc::c_long> = Some(447);
+#[cfg(not(any(
+    all(target_arch = "x86_64", target_pointer_width = "64"),
+    target_arch = "aarch64",
+    target_arch = "riscv64",
+    target_arch = "s390x",
+)))]
+const SYS_MEMFD_SECRET: Option<libc::c_long> = None;
+
+/// Overwrite memory with zeros in a way the compiler will not optimize away.
+pub(crate) fn zeroize(buffer: &mut [u8]) {
+    for byte in buffer.iter_mut() {
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+/// A placeholder for redacted data in `Debug` output.
+pub(crate) struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// A payload which is wiped when dropped if it was formatted into a new allocation.
+///
+/// Borrowed payloads are left alone since their owner is responsible for them. See
+/// `KeyPayload::wiped_payload`.
+pub struct WipedPayload<'a>(Cow<'a, [u8]>);
+
+impl<'a> WipedPayload<'a> {
+    /// Wrap a payload so that it is wiped when dropped.
+    pub fn new(payload: Cow<'a, [u8]>) -> Self {
+        WipedPayload(payload)
+    }
+}
+
+impl fmt::Debug for WipedPayload<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WipedPayload").field(&Redacted).finish()
+    }
+}
+
+impl Deref for WipedPayload<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for WipedPayload<'_> {
+    fn drop(&mut self) {
+        if let Cow::Owned(payload) = &mut self.0 {
+            zeroize(payload);
+        }
+    }
+}
+
+enum Storage {
+    /// Memory on the heap which is locked into RAM if possible.
+    Heap { data: Box<[u8]>, locked: bool },
+    /// A mapping of a `memfd_secret` file.
+    MemfdSecret { ptr: NonNull<u8>, size: usize },
+}
+
+/// A buffer for secret data.
+///
+/// The memory of the buffer is locked so that it is not written to swap and is overwritten with
+/// zeros when the buffer is dropped. Locking memory is subject to `RLIMIT_MEMLOCK`; see
+/// `is_locked`. The buffer may instead be backed by `memfd_secret(2)` which additionally removes
+/// the memory from the kernel's own mappings.
+///
+/// The contents are never printed by its `Debug` implementation and it may not be cloned. It
+/// dereferences to `[u8]` and may be used as the payload of key types which accept bytes (such
+/// as `User` or `Logon`).
+pub struct SecretBuffer {
+    storage: Storage,
+    capacity: usize,
+    len: usize,
+}
+
+// The buffer uniquely owns its memory.
+unsafe impl Send for SecretBuffer {}
+unsafe impl Sync for SecretBuffer {}
+
+impl SecretBuffer {
+    /// Create a zero-filled buffer of `len` bytes.
+    pub fn new(len: usize) -> Self {
+        let data = vec![0; len].into_boxed_slice();
+        let locked = len == 0
+            || unsafe { libc::mlock(data.as_ptr() as *const libc::c_void, data.len()) } == 0;
+        SecretBuffer {
+            storage: Storage::Heap {
+                data,
+                locked,
+            },
+            capacity: len,
+            len,
+        }
+    }
+
+    /// Create a zero-filled buffer of `len` bytes backed by `memfd_secret(2)`.
+    ///
+    /// Fails with `ENOSYS` if the kernel does not support secret memory.
+    pub fn new_memfd_secret(len: usize) -> Result<Self> {
+        let sysno = SYS_MEMFD_SECRET
+            .ok_or_else(|| Error::new(Operation::MemfdSecret, errno::Errno(libc::ENOSYS)))?;
+        // Mappings may not be empty.
+        let size = len.max(1);
+
+        let fd = unsafe { libc::syscall(sysno, libc::O_CLOEXEC) };
+        if fd < 0 {
+            return Err(Error::new(Operation::MemfdSecret, errno::errno()));
+        }
+        let fd = fd as libc::c_int;
+
+        let ret = unsafe { libc::ftruncate(fd, size as libc::off_t) };
+        let ptr = if ret < 0 {
+            libc::MAP_FAILED
+        } else {
+            unsafe {
+                libc::mmap(
+                    ptr::null_mut(),
+                    size,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    fd,
+                    0,
+                )
+            }
+        };
+        // Save the error before `close` can clobber it.
+        let err = errno::errno();
+        unsafe { libc::close(fd) };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(Error::new(Operation::MemfdSecret, err));
+        }
+
+        Ok(SecretBuffer {
+            storage: Storage::MemfdSecret {
+                ptr: NonNull::new(ptr as *mut u8).expect("mmap does not return NULL"),
+                size,
+            },
+            capacity: len,
+            len,
+        })
+    }
+
+    /// Create a buffer holding a copy of `data`.
+    pub fn from_slice(data: &[u8]) -> Self {
+        let mut buffer = Self::new(data.len());
+        buffer.copy_from_slice(data);
+        buffer
+    }
+
+    fn storage(&self) -> &[u8] {
+        match &self.storage {
+            Storage::Heap {
+                data, ..
+            } => data,
+            Storage::MemfdSecret {
+                ptr,
+                size,
+            } => unsafe { slice::from_raw_parts(ptr.as_ptr(), *size) },
+        }
+    }
+
+    fn storage_mut(&mut self) -> &mut [u8] {
+        match &mut self.storage {
+            Storage::Heap {
+                data, ..
+            } => data,
+            Storage::MemfdSecret {
+                ptr,
+                size,
+            } => unsafe { slice::from_raw_parts_mut(ptr.as_ptr(), *size) },
+        }
+    }
+
+    /// The number of bytes the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Shorten the buffer to `len` bytes.
+    ///
+    /// Has no effect if `len` is not smaller than the current length.
+    pub fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    /// Whether the memory is locked into RAM.
+    pub fn is_locked(&self) -> bool {
+        match self.storage {
+            Storage::Heap {
+                locked, ..
+            } => locked,
+            // Secret memory is always locked.
+            Storage::MemfdSecret {
+                ..
+            } => true,
+        }
+    }
+
+    /// Whether the buffer is backed by `memfd_secret(2)`.
+    pub fn is_memfd_secret(&self) -> bool {
+        matches!(self.storage, Storage::MemfdSecret { .. })
+    }
+
+    /// The full storage of the buffer, ignoring its length.
+    pub(crate) fn spare_storage(&mut self) -> &mut [u8] {
+        let capacity = self.capacity;
+        &mut self.storage_mut()[..capacity]
+    }
+
+    /// Set the length of the buffer after writing into `spare_storage`.
+    pub(crate) fn set_filled(&mut self, len: usize) {
+        assert!(len <= self.capacity);
+        self.len = len;
+    }
+}
+
+impl Drop for SecretBuffer {
+    fn drop(&mut self) {
+        zeroize(self.storage_mut());
+        match &self.storage {
+            Storage::Heap {
+                data,
+                locked,
+            } => {
+                if *locked && !data.is_empty() {
+                    unsafe { libc::munlock(data.as_ptr() as *const libc::c_void, data.len()) };
+                }
+            },
+            Storage::MemfdSecret {
+                ptr,
+                size,
+            } => {
+                unsafe { libc::munmap(ptr.as_ptr() as *mut libc::c_void, *size) };
+            },
+        }
+    }
+}
+
+impl Deref for SecretBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.storage()[..self.len]
+    }
+}
+
+impl DerefMut for SecretBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let len = self.len;
+        &mut self.storage_mut()[..len]
+    }
+}
+
+impl AsRef<[u8]> for SecretBuffer {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl AsMut<[u8]> for SecretBuffer {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl Borrow<[u8]> for SecretBuffer {
+    fn borrow(&self) -> &[u8] {
+        self
+    }
+}
+
+impl BorrowMut<[u8]> for SecretBuffer {
+    fn borrow_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl Borrow<[u8]> for &SecretBuffer {
+    fn borrow(&self) -> &[u8] {
+        self
+    }
+}
+
+impl KeyPayload for SecretBuffer {
+    fn payload(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl fmt::Debug for SecretBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SecretBuffer")
+            .field("len", &self.len)
+            .field("data", &Redacted)
+            .finish()
+    }
+}
+
+impl PartialEq for SecretBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        // Compare without exiting early to avoid leaking information through timing.
+        self.len == other.len
+            && self
+                .iter()
+                .zip(other.iter())
+                .fold(0, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+impl Eq for SecretBuffer {}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::{SecretBuffer, WipedPayload};
+    use crate::keytype::KeyPayload;
+
+    #[test]
+    fn test_new() {
+        let buffer = SecretBuffer::new(16);
+        assert_eq!(buffer.len(), 16);
+        assert_eq!(buffer.capacity(), 16);
+        assert!(buffer.iter().all(|&b| b == 0));
+        assert!(!buffer.is_memfd_secret());
+
+        let buffer = SecretBuffer::new(0);
+        assert!(buffer.is_empty());
+        assert!(buffer.is_locked());
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let mut buffer = SecretBuffer::from_slice(b"secret");
+        assert_eq!(&*buffer, b"secret");
+
+        buffer.truncate(3);
+        assert_eq!(&*buffer, b"sec");
+        assert_eq!(buffer.capacity(), 6);
+        buffer.truncate(5);
+        assert_eq!(&*buffer, b"sec");
+    }
+
+    #[test]
+    fn test_debug_redacted() {
+        let buffer = SecretBuffer::from_slice(b"secret");
+        let debug = format!("{:?}", buffer);
+        assert!(!debug.contains("secret"));
+        assert!(!debug.contains("115"));
+        assert_eq!(debug, "SecretBuffer { len: 6, data: <redacted> }");
+    }
+
+    #[test]
+    fn test_wiped_payload() {
+        let payload = "secret".to_string();
+        let wiped = payload.wiped_payload();
+        assert_eq!(&*wiped, b"secret");
+        assert_eq!(format!("{:?}", wiped), "WipedPayload(<redacted>)");
+
+        let wiped = WipedPayload::new(Cow::Owned(b"secret".to_vec()));
+        assert!(!format!("{:?}", wiped).contains("secret"));
+    }
+
+    #[test]
+    fn test_eq() {
+        assert_eq!(
+            SecretBuffer::from_slice(b"secret"),
+            SecretBuffer::from_slice(b"secret"),
+        );
+        assert_ne!(
+            SecretBuffer::from_slice(b"secret"),
+            SecretBuffer::from_slice(b"secreT"),
+        );
+        assert_ne!(
+            SecretBuffer::from_slice(b"secret"),
+            SecretBuffer::from_slice(b"secrets"),
+        );
+    }
+
+    #[test]
+    fn test_memfd_secret() {
+        let mut buffer = match SecretBuffer::new_memfd_secret(16) {
+            Ok(buffer) => buffer,
+            // Secret memory may be unsupported or disabled.
+            Err(err) => {
+                assert!([libc::ENOSYS, libc::EPERM, libc::ENOMEM].contains(&err.errno().0));
+                return;
+            },
+        };
+        assert!(buffer.is_memfd_secret());
+        assert!(buffer.is_locked());
+        assert_eq!(buffer.len(), 16);
+        buffer.copy_from_slice(b"0123456789abcdef");
+        assert_eq!(&*buffer, b"0123456789abcdef");
+
+        let buffer = SecretBuffer::new_memfd_secret(0).unwrap();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.capacity(), 0);
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use keyutils_raw::{KeyringSerial, KEY_SPEC_REQKEY_AUTH_KEY};
+
+use crate::api::{Key, KeyManager, Keyring};
+use crate::constants::SpecialKeyring;
+use crate::error::{Error, Operation, Result};
+use crate::keytype::KeyType;
+use crate::keytypes;
+
+/// The names `keyctl(1)` uses for special keyrings.
+const SPECIAL_NAMES: [(&str, SpecialKeyring); 7] = [
+    ("@t", SpecialKeyring::Thread),
+    ("@p", SpecialKeyring::Process),
+    ("@s", SpecialKeyring::Session),
+    ("@u", SpecialKeyring::User),
+    ("@us", SpecialKeyring::UserSession),
+    ("@g", SpecialKeyring::Group),
+    ("@aa", SpecialKeyring::Requestor),
+];
+
+/// The name `keyctl(1)` uses for the request-key authorization key.
+const REQUEST_KEY_AUTH_NAME: &str = "@a";
+
+/// A reference to a key or keyring in the syntax used by `keyctl(1)`.
+///
+/// The supported forms are:
+///
+///   - `@t`, `@p`, `@s`, `@u`, `@us`, `@g`, `@aa`: special keyrings;
+///   - `@a`: the request-key authorization key of the current thread;
+///   - `1234` or `0x4d2`: a key serial number;
+///   - `%type:description`: a key found by its type and description; and
+///   - `%:name`: a keyring found by its name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySpecifier {
+    /// A special keyring.
+    Special(SpecialKeyring),
+    /// The request-key authorization key of the current thread.
+    RequestKeyAuth,
+    /// A key serial number.
+    Serial(KeyringSerial),
+    /// A key found by its type and description.
+    Lookup {
+        /// The type of the key.
+        type_: String,
+        /// The description of the key.
+        description: String,
+    },
+}
+
+impl KeySpecifier {
+    /// Look up the serial of a special keyring, optionally creating it.
+    fn special_serial(special: SpecialKeyring, create: bool) -> Result<KeyringSerial> {
+        let keyring = if create {
+            Keyring::attach_or_create(special)
+        } else {
+            Keyring::attach(special)
+        };
+        keyring.map(|keyring| keyring.serial())
+    }
+
+    /// Resolve the specifier to a key.
+    ///
+    /// Fails with `EISDIR` if the specifier refers to a keyring. Lookups search the process's
+    /// keyrings and then all keys which the process may view (see `Key::find_dyn`).
+    pub fn resolve_key(&self) -> Result<Key> {
+        match self {
+            KeySpecifier::Special(special) => {
+                let id = Self::special_serial(*special, false)?;
+                Err(Error::new(Operation::Describe, errno::Errno(libc::EISDIR))
+                    .with_serial(id)
+                    .with_key_type(keytypes::Keyring::name()))
+            },
+            KeySpecifier::RequestKeyAuth => KeyManager::request_key_auth_key(false),
+            KeySpecifier::Serial(id) => Key::from_serial_checked(*id),
+            KeySpecifier::Lookup {
+                type_,
+                description,
+            } => {
+                let key = Key::find_dyn(type_, description, None)?;
+                if type_ == keytypes::Keyring::name() {
+                    Err(Error::new(Operation::Describe, errno::Errno(libc::EISDIR))
+                        .with_serial(key.serial())
+                        .with_key_type(keytypes::Keyring::name()))
+                } else {
+                    Ok(key)
+                }
+            },
+        }
+    }
+
+    fn resolve_keyring_impl(&self, create: bool) -> Result<Keyring> {
+        let id = match self {
+            KeySpecifier::Special(special) => Self::special_serial(*special, create)?,
+            KeySpecifier::RequestKeyAuth => KeyManager::request_key_auth_key(false)?.serial(),
+            KeySpecifier::Serial(id) => *id,
+            KeySpecifier::Lookup {
+                type_,
+                description,
+            } => Key::find_dyn(type_, description, None)?.serial(),
+        };
+        Keyring::from_serial_checked(id)
+    }
+
+    /// Resolve the specifier to a keyring.
+    ///
+    /// Fails with `ENOTDIR` if the specifier refers to a key. Special keyrings must already
+    /// exist.
+    pub fn resolve_keyring(&self) -> Result<Keyring> {
+        self.resolve_keyring_impl(false)
+    }
+
+    /// Resolve the specifier to a keyring, creating special keyrings if needed.
+    ///
+    /// See `resolve_keyring`.
+    pub fn resolve_keyring_or_create(&self) -> Result<Keyring> {
+        self.resolve_keyring_impl(true)
+    }
+}
+
+impl From<SpecialKeyring> for KeySpecifier {
+    fn from(special: SpecialKeyring) -> Self {
+        KeySpecifier::Special(special)
+    }
+}
+
+impl From<KeyringSerial> for KeySpecifier {
+    fn from(id: KeyringSerial) -> Self {
+        KeySpecifier::Serial(id)
+    }
+}
+
+/// An error parsing a `KeySpecifier`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeySpecifierError {
+    input: String,
+}
+
+impl ParseKeySpecifierError {
+    /// The specifier which could not be parsed.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+impl fmt::Display for ParseKeySpecifierError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid key specifier `{}`", self.input)
+    }
+}
+
+impl error::Error for ParseKeySpecifierError {}
+
+/// Parse a serial number in decimal or hexadecimal (with a `0x` prefix).
+fn parse_serial(s: &str) -> Option<i32> {
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        i32::from_str_radix(hex, 16).ok()
+    } else {
+        s.parse().ok()
+    }
+}
+
+impl FromStr for KeySpecifier {
+    type Err = ParseKeySpecifierError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let err = || {
+            ParseKeySpecifierError {
+                input: s.into(),
+            }
+        };
+
+        if s == REQUEST_KEY_AUTH_NAME {
+            return Ok(KeySpecifier::RequestKeyAuth);
+        }
+        if let Some(&(_, special)) = SPECIAL_NAMES.iter().find(|&&(name, _)| name == s) {
+            return Ok(KeySpecifier::Special(special));
+        }
+
+        if let Some(lookup) = s.strip_prefix('%') {
+            let (type_, description) = lookup.split_once(':').ok_or_else(err)?;
+            let type_ = if type_.is_empty() {
+                keytypes::Keyring::name()
+            } else {
+                type_
+            };
+            return Ok(KeySpecifier::Lookup {
+                type_: type_.into(),
+                description: description.into(),
+            });
+        }
+
+        let serial = parse_serial(s).ok_or_else(err)?;
+        if serial > 0 {
+            return Ok(KeySpecifier::Serial(
+                KeyringSerial::new(serial).ok_or_else(err)?,
+            ));
+        }
+
+        // `keyctl(1)` also accepts the raw values of the special IDs.
+        if serial == KEY_SPEC_REQKEY_AUTH_KEY.get() {
+            return Ok(KeySpecifier::RequestKeyAuth);
+        }
+        SpecialKeyring::ALL
+            .iter()
+            .find(|special| special.spec().get() == serial)
+            .map(|&special| KeySpecifier::Special(special))
+            .ok_or_else(err)
+    }
+}
+
+impl fmt::Display for KeySpecifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeySpecifier::Special(special) => {
+                let name = SPECIAL_NAMES
+                    .iter()
+                    .find(|&&(_, s)| s == *special)
+                    .map(|&(name, _)| name)
+                    .expect("all special keyrings have names");
+                f.write_str(name)
+            },
+            KeySpecifier::RequestKeyAuth => f.write_str(REQUEST_KEY_AUTH_NAME),
+            KeySpecifier::Serial(id) => write!(f, "{}", id),
+            KeySpecifier::Lookup {
+                type_,
+                description,
+            } => {
+                let type_ = if type_ == keytypes::Keyring::name() {
+                    ""
+                } else {
+                    type_
+                };
+                write!(f, "%{}:{}", type_, description)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use keyutils_raw::KeyringSerial;
+
+    use crate::SpecialKeyring;
+
+    use super::KeySpecifier;
+
+    fn parse(s: &str) -> KeySpecifier {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_special() {
+        assert_eq!(parse("@t"), KeySpecifier::Special(SpecialKeyring::Thread));
+        assert_eq!(parse("@p"), KeySpecifier::Special(SpecialKeyring::Process));
+        assert_eq!(parse("@s"), KeySpecifier::Special(SpecialKeyring::Session));
+        assert_eq!(parse("@u"), KeySpecifier::Special(SpecialKeyring::User));
+        assert_eq!(
+            parse("@us"),
+            KeySpecifier::Special(SpecialKeyring::UserSession),
+        );
+        assert_eq!(parse("@g"), KeySpecifier::Special(SpecialKeyring::Group));
+        assert_eq!(
+            parse("@aa"),
+            KeySpecifier::Special(SpecialKeyring::Requestor),
+        );
+        assert_eq!(parse("@a"), KeySpecifier::RequestKeyAuth);
+        assert_eq!(parse("-3"), KeySpecifier::Special(SpecialKeyring::Session));
+        assert_eq!(parse("-7"), KeySpecifier::RequestKeyAuth);
+        assert_eq!(
+            parse("-8"),
+            KeySpecifier::Special(SpecialKeyring::Requestor),
+        );
+    }
+
+    #[test]
+    fn test_parse_serial() {
+        let serial = KeySpecifier::Serial(KeyringSerial::new(1234).unwrap());
+        assert_eq!(parse("1234"), serial);
+        assert_eq!(parse("0x4d2"), serial);
+        assert_eq!(parse("0X4D2"), serial);
+    }
+
+    #[test]
+    fn test_parse_lookup() {
+        assert_eq!(
+            parse("%user:foo:bar"),
+            KeySpecifier::Lookup {
+                type_: "user".into(),
+                description: "foo:bar".into(),
+            },
+        );
+        assert_eq!(
+            parse("%:_ses"),
+            KeySpecifier::Lookup {
+                type_: "keyring".into(),
+                description: "_ses".into(),
+            },
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in &["", "@", "@x", "0", "-7000", "0x", "12a", "%user", "%"] {
+            let err = s.parse::<KeySpecifier>().unwrap_err();
+            assert_eq!(err.input(), *s);
+        }
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        for s in &[
+            "@t",
+            "@p",
+            "@s",
+            "@u",
+            "@us",
+            "@g",
+            "@aa",
+            "@a",
+            "1234",
+            "%user:foo:bar",
+            "%:_ses",
+        ] {
+            assert_eq!(parse(s).to_string(), *s);
+        }
+        assert_eq!(parse("0x4d2").to_string(), "1234");
+        assert_eq!(parse("%keyring:name").to_string(), "%:name");
+    }
+}
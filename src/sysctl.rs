@@ -0,0 +1,157 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::fmt::Display;
+use std::fs;
+use std::str::FromStr;
+use std::time::Duration;
+
+use log::error;
+
+use crate::error::{Error, Operation, Result};
+
+const SYSCTL_DIR: &str = "/proc/sys/kernel/keys";
+
+const MAXKEYS: &str = "maxkeys";
+const MAXBYTES: &str = "maxbytes";
+const ROOT_MAXKEYS: &str = "root_maxkeys";
+const ROOT_MAXBYTES: &str = "root_maxbytes";
+const GC_DELAY: &str = "gc_delay";
+const PERSISTENT_KEYRING_EXPIRY: &str = "persistent_keyring_expiry";
+
+/// Tunables of the keys subsystem from `/proc/sys/kernel/keys`.
+///
+/// Changing the values requires the SysAdmin capability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeySysctl {
+    /// The maximum number of keys a non-root user may own.
+    pub maxkeys: usize,
+    /// The maximum number of bytes a non-root user may use for keys.
+    pub maxbytes: usize,
+    /// The maximum number of keys the root user may own.
+    pub root_maxkeys: usize,
+    /// The maximum number of bytes the root user may use for keys.
+    pub root_maxbytes: usize,
+    /// How long dead keys are kept before being garbage collected.
+    pub gc_delay: Duration,
+    /// How long a persistent keyring lives after it was last accessed.
+    pub persistent_keyring_expiry: Duration,
+}
+
+fn read_sysctl<T>(name: &str) -> Result<T>
+where
+    T: FromStr,
+{
+    let path = format!("{}/{}", SYSCTL_DIR, name);
+    let value =
+        fs::read_to_string(&path).map_err(|err| Error::from_io(Operation::KeySysctl, &err))?;
+    value.trim().parse().map_err(|_| {
+        error!(
+            "New {} format detected! Please report this upstream to \
+             https://github.com/mathstuf/rust-keyutils: {}",
+            path, value,
+        );
+        Error::new(Operation::KeySysctl, errno::Errno(libc::EINVAL))
+    })
+}
+
+fn write_sysctl<T>(name: &str, value: T) -> Result<()>
+where
+    T: Display,
+{
+    let path = format!("{}/{}", SYSCTL_DIR, name);
+    fs::write(path, format!("{}\n", value))
+        .map_err(|err| Error::from_io(Operation::KeySysctl, &err))
+}
+
+fn read_seconds(name: &str) -> Result<Duration> {
+    read_sysctl(name).map(Duration::from_secs)
+}
+
+impl KeySysctl {
+    /// Read all of the tunables.
+    pub fn read() -> Result<Self> {
+        Ok(KeySysctl {
+            maxkeys: read_sysctl(MAXKEYS)?,
+            maxbytes: read_sysctl(MAXBYTES)?,
+            root_maxkeys: read_sysctl(ROOT_MAXKEYS)?,
+            root_maxbytes: read_sysctl(ROOT_MAXBYTES)?,
+            gc_delay: read_seconds(GC_DELAY)?,
+            persistent_keyring_expiry: Self::read_persistent_keyring_expiry()?,
+        })
+    }
+
+    /// Read the maximum number of keys and bytes a user may own.
+    ///
+    /// The root user has separate limits.
+    pub(crate) fn read_quota_limits(uid: libc::uid_t) -> Result<(usize, usize)> {
+        if uid == 0 {
+            Ok((read_sysctl(ROOT_MAXKEYS)?, read_sysctl(ROOT_MAXBYTES)?))
+        } else {
+            Ok((read_sysctl(MAXKEYS)?, read_sysctl(MAXBYTES)?))
+        }
+    }
+
+    /// Read how long a persistent keyring lives after it was last accessed.
+    pub fn read_persistent_keyring_expiry() -> Result<Duration> {
+        read_seconds(PERSISTENT_KEYRING_EXPIRY)
+    }
+
+    /// Set the maximum number of keys a non-root user may own.
+    pub fn set_maxkeys(maxkeys: usize) -> Result<()> {
+        write_sysctl(MAXKEYS, maxkeys)
+    }
+
+    /// Set the maximum number of bytes a non-root user may use for keys.
+    pub fn set_maxbytes(maxbytes: usize) -> Result<()> {
+        write_sysctl(MAXBYTES, maxbytes)
+    }
+
+    /// Set the maximum number of keys the root user may own.
+    pub fn set_root_maxkeys(maxkeys: usize) -> Result<()> {
+        write_sysctl(ROOT_MAXKEYS, maxkeys)
+    }
+
+    /// Set the maximum number of bytes the root user may use for keys.
+    pub fn set_root_maxbytes(maxbytes: usize) -> Result<()> {
+        write_sysctl(ROOT_MAXBYTES, maxbytes)
+    }
+
+    /// Set how long dead keys are kept before being garbage collected.
+    ///
+    /// The delay is truncated to whole seconds.
+    pub fn set_gc_delay(delay: Duration) -> Result<()> {
+        write_sysctl(GC_DELAY, delay.as_secs())
+    }
+
+    /// Set how long a persistent keyring lives after it was last accessed.
+    ///
+    /// The expiry is truncated to whole seconds. It applies the next time a persistent keyring is
+    /// accessed.
+    pub fn set_persistent_keyring_expiry(expiry: Duration) -> Result<()> {
+        write_sysctl(PERSISTENT_KEYRING_EXPIRY, expiry.as_secs())
+    }
+}
@@ -25,6 +25,7 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::keytypes::User;
+use crate::{KeyQuota, KeySysctl};
 
 use super::utils;
 use super::utils::kernel::*;
@@ -65,6 +66,16 @@ fn overlong_key_type() {
     assert_eq!(err, errno::Errno(libc::EINVAL));
 }
 
+#[test]
+fn unsupported_key_type_dyn() {
+    let mut keyring = utils::new_test_keyring();
+    let err = keyring
+        .add_key_dyn("unsupported_key_type_dyn", "", b"")
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENODEV));
+    assert_eq!(err.key_type(), Some("unsupported_key_type_dyn"));
+}
+
 #[test]
 fn keyring_with_payload() {
     let mut keyring = utils::new_test_keyring();
@@ -102,6 +113,25 @@ fn overlong_user_description() {
     assert_eq!(err, errno::Errno(libc::EINVAL));
 }
 
+#[test]
+fn nul_in_user_description() {
+    let mut keyring = utils::new_test_keyring();
+    let err = keyring
+        .add_key::<User, _, _>("nul\0description", &b"payload"[..])
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn overlong_user_payload() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = vec![0; 32768];
+    let err = keyring
+        .add_key::<User, _, _>("overlong_user_payload", payload)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
 #[test]
 fn invalid_keyring() {
     let mut keyring = utils::invalid_keyring();
@@ -195,3 +225,59 @@ fn add_keyring_replace() {
     assert!(keys.is_empty());
     assert!(keyrings.is_empty());
 }
+
+#[test]
+fn check_user_quota() {
+    let keyring = utils::new_test_keyring();
+
+    let description = "check_user_quota";
+    let payload = &b"payload"[..];
+    let check = keyring
+        .check_quota::<User, _, _>(description, payload)
+        .unwrap();
+    assert_eq!(check.quota.uid, *UID);
+    assert_eq!(check.bytes_needed, description.len() + 1 + payload.len());
+    assert!(check.fits());
+}
+
+#[test]
+fn quota_without_keys() {
+    // The kernel does not list users without any keys.
+    let uid = 54321;
+    assert!(KeyQuota::all()
+        .unwrap()
+        .iter()
+        .all(|quota| quota.uid != uid));
+
+    let sysctl = KeySysctl::read().unwrap();
+    let quota = KeyQuota::for_uid(uid).unwrap();
+    assert_eq!(quota.uid, uid);
+    assert_eq!(quota.nkeys, 0);
+    assert_eq!(quota.qnkeys, 0);
+    assert_eq!(quota.qnbytes, 0);
+    assert_eq!(quota.maxkeys, sysctl.maxkeys);
+    assert_eq!(quota.maxbytes, sysctl.maxbytes);
+    assert!(quota.check("quota_without_keys", b"payload").fits());
+}
+
+#[test]
+fn add_key_checked() {
+    let mut keyring = utils::new_test_keyring();
+
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key_checked::<User, _, _>("add_key_checked", payload)
+        .unwrap();
+    assert_eq!(key.read().unwrap(), payload);
+    key.invalidate().unwrap();
+}
+
+#[test]
+fn overlong_user_payload_checked() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = vec![0; 32768];
+    let err = keyring
+        .add_key_checked::<User, _, _>("overlong_user_payload_checked", payload)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
@@ -0,0 +1,221 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::Duration;
+
+use crate::keytypes::User;
+use crate::{KeyHandle, KeySysctl, KeyTimeout, Permission};
+
+use super::utils;
+use super::utils::kernel::*;
+
+#[test]
+fn create_key() {
+    let mut keyring = utils::new_test_keyring();
+    let perms = Permission::POSSESSOR_ALL | Permission::USER_VIEW;
+    let key = keyring
+        .create::<User, _, _>("create_key", &b"payload"[..])
+        .permissions(perms)
+        .owner(*UID, *GID)
+        .timeout(Duration::from_secs(100))
+        .add()
+        .unwrap();
+
+    let description = key.description().unwrap();
+    assert_eq!(description.perms, perms);
+    assert_eq!(description.uid, *UID);
+    assert_eq!(description.gid, *GID);
+    assert_eq!(key.read().unwrap(), b"payload");
+
+    match key.info().unwrap().timeout {
+        KeyTimeout::Remaining(remaining) => assert!(remaining <= Duration::from_secs(100)),
+        timeout => panic!("unexpected timeout: {:?}", timeout),
+    }
+}
+
+#[test]
+fn create_key_defaults() {
+    let mut keyring = utils::new_test_keyring();
+    let created = keyring
+        .create::<User, _, _>("create_key_defaults", &b"payload"[..])
+        .add()
+        .unwrap();
+    let added = keyring
+        .add_key::<User, _, _>("create_key_defaults_added", &b"payload"[..])
+        .unwrap();
+
+    assert_eq!(
+        created.description().unwrap().perms,
+        added.description().unwrap().perms,
+    );
+    assert_eq!(created.info().unwrap().timeout, KeyTimeout::Permanent);
+}
+
+#[test]
+fn create_key_masked() {
+    let mut keyring = utils::new_test_keyring();
+    let mask = Permission::USER_WRITE | Permission::USER_SET_ATTRIBUTE;
+    keyring.set_permission_mask(mask);
+    assert_eq!(keyring.permission_mask(), mask);
+
+    let key = keyring
+        .create::<User, _, _>("create_key_masked", &b"payload"[..])
+        .permissions(Permission::POSSESSOR_ALL | Permission::USER_ALL)
+        .add()
+        .unwrap();
+    assert_eq!(
+        key.description().unwrap().perms,
+        Permission::POSSESSOR_ALL | (Permission::USER_ALL - mask),
+    );
+
+    // The default permissions are masked as well.
+    let key = keyring
+        .create::<User, _, _>("create_key_masked_default", &b"payload"[..])
+        .add()
+        .unwrap();
+    let perms = key.description().unwrap().perms;
+    assert!(!perms.intersects(mask));
+    assert!(perms.contains(Permission::POSSESSOR_ALL));
+}
+
+#[test]
+fn create_keyring() {
+    let mut keyring = utils::new_test_keyring();
+    let mask = Permission::OTHER_ALL;
+    keyring.set_permission_mask(mask);
+
+    let perms = Permission::POSSESSOR_ALL | Permission::USER_VIEW | Permission::OTHER_VIEW;
+    let mut new_keyring = keyring
+        .create_keyring("create_keyring")
+        .permissions(perms)
+        .add()
+        .unwrap();
+    assert_eq!(new_keyring.description().unwrap().perms, perms - mask);
+    assert_eq!(new_keyring.permission_mask(), mask);
+
+    let (_, keyrings) = keyring.read().unwrap();
+    assert_eq!(keyrings, [new_keyring.clone()]);
+
+    // The mask is inherited by keys created in the new keyring.
+    let key = new_keyring
+        .create::<User, _, _>("create_keyring_key", &b"payload"[..])
+        .add()
+        .unwrap();
+    assert!(!key.description().unwrap().perms.intersects(mask));
+}
+
+#[test]
+fn create_key_overlong_payload() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = vec![0; 32768];
+    let err = keyring
+        .create::<User, _, _>("create_key_overlong_payload", payload)
+        .add()
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn create_key_invalidated_on_failure() {
+    // Changing the owner of a key charges it to the new owner's quota, so a payload larger than
+    // the quota of a normal user causes the ownership change to fail.
+    let maxbytes = KeySysctl::read().unwrap().maxbytes;
+    let payload_size = maxbytes + 1;
+    if *UID != 0 || payload_size > 32767 {
+        return;
+    }
+
+    let mut keyring = utils::new_test_keyring();
+    let payload = vec![0; payload_size];
+    let err = keyring
+        .create::<User, _, _>("create_key_invalidated_on_failure", payload)
+        .owner(43210, 43210)
+        .add()
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EDQUOT));
+
+    // Invalidated keys are reported as revoked until they are garbage collected.
+    let err = keyring
+        .search_for_key::<User, _, _>("create_key_invalidated_on_failure", None)
+        .unwrap_err();
+    assert!(err == errno::Errno(libc::ENOKEY) || err == errno::Errno(libc::EKEYREVOKED));
+}
+
+#[test]
+fn create_key_existing() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("create_key_existing", &b"payload"[..])
+        .unwrap();
+    let desc = key.description().unwrap();
+
+    // The builder must not update, change, or invalidate a key it did not create.
+    let err = keyring
+        .create::<User, _, _>("create_key_existing", &b"updated"[..])
+        .permissions(Permission::POSSESSOR_VIEW)
+        .owner(43210, 43210)
+        .add()
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EEXIST));
+
+    assert_eq!(key.read().unwrap(), b"payload");
+    let current = key.description().unwrap();
+    assert_eq!(current.uid, desc.uid);
+    assert_eq!(current.gid, desc.gid);
+    assert_eq!(current.perms, desc.perms);
+}
+
+#[test]
+fn create_key_unlinkable() {
+    let mut keyring = utils::new_test_keyring();
+    // The key is moved into the keyring after it is built, which requires `link` permission.
+    let perms = Permission::POSSESSOR_VIEW | Permission::USER_VIEW;
+    let key = keyring
+        .create::<User, _, _>("create_key_unlinkable", &b"payload"[..])
+        .permissions(perms)
+        .add()
+        .unwrap();
+
+    assert_eq!(key.description().unwrap().perms, perms);
+    let (keys, _) = keyring.read().unwrap();
+    assert_eq!(keys, [key.into_key()]);
+}
+
+#[test]
+fn create_key_existing_nested() {
+    let mut keyring = utils::new_test_keyring();
+    let mut nested = keyring.add_keyring("create_key_existing_nested").unwrap();
+    nested
+        .add_key::<User, _, _>("create_key_existing_nested", &b"payload"[..])
+        .unwrap();
+
+    // Only keys linked directly into the keyring conflict.
+    let key = keyring
+        .create::<User, _, _>("create_key_existing_nested", &b"created"[..])
+        .add()
+        .unwrap();
+    assert_eq!(key.read().unwrap(), b"created");
+}
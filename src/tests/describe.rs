@@ -24,8 +24,8 @@
 // (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::keytypes::{Keyring, User};
-use crate::{Key, KeyType, Permission};
+use crate::keytypes::{logon, Keyring, Logon, User};
+use crate::{Key, KeyHandle, KeyType, Permission};
 
 use super::utils;
 use super::utils::kernel::*;
@@ -125,3 +125,85 @@ fn describe_revoked_key() {
     let err = key_mirror.description().unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYREVOKED));
 }
+
+#[test]
+fn describe_typed_key() {
+    let mut keyring = utils::new_test_keyring();
+    let description = logon::Description {
+        subtype: "describe".into(),
+        description: "typed:key".into(),
+    };
+    let key = keyring
+        .add_key::<Logon, _, _>(&description, &b"payload"[..])
+        .unwrap();
+
+    assert_eq!(key.typed_description().unwrap(), description);
+
+    let err = key.as_ref().typed_description::<User>().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+fn check_handle_description<H: KeyHandle>(handle: &H, type_: &str, description: &str) {
+    let desc = handle.description().unwrap();
+    assert_eq!(desc.type_, type_);
+    assert_eq!(desc.description, description);
+}
+
+#[test]
+fn describe_generic_handles() {
+    let mut keyring = utils::new_test_keyring();
+    let new_keyring = keyring.add_keyring("describe_generic_keyring").unwrap();
+    let key = keyring
+        .add_key::<User, _, _>("describe_generic_key", &b"payload"[..])
+        .unwrap();
+
+    check_handle_description(&new_keyring, Keyring::name(), "describe_generic_keyring");
+    check_handle_description(&key, User::name(), "describe_generic_key");
+    check_handle_description(key.as_ref(), User::name(), "describe_generic_key");
+}
+
+#[test]
+fn describe_key_into() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("describe_key_into", &b"payload"[..])
+        .unwrap();
+    let perms = key.description().unwrap().perms;
+    let expected = format!(
+        "user;{};{};{:08x};describe_key_into",
+        *UID,
+        *GID,
+        perms.bits(),
+    );
+
+    let mut buffer = [0; 128];
+    let sz = key.describe_into(&mut buffer).unwrap();
+    // The size includes the trailing NUL.
+    assert_eq!(sz, expected.len() + 1);
+    assert_eq!(&buffer[..sz], format!("{}\0", expected).as_bytes());
+
+    let mut small_buffer = [0; 4];
+    let sz = key.describe_into(&mut small_buffer).unwrap();
+    assert_eq!(sz, expected.len() + 1);
+}
+
+#[test]
+fn describe_key_into_vec() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("into_vec", &b"payload"[..])
+        .unwrap();
+
+    let mut buffer = Vec::new();
+    keyring.describe_into_vec(&mut buffer).unwrap();
+    assert!(buffer.starts_with(b"keyring;"));
+
+    // The key's description is shorter than the keyring's, so the allocation is reused.
+    let capacity = buffer.capacity();
+    let sz = key.describe_into_vec(&mut buffer).unwrap();
+    assert_eq!(sz, buffer.len());
+    let desc = String::from_utf8(buffer.clone()).unwrap();
+    assert!(desc.starts_with("user;"));
+    assert!(desc.ends_with(";into_vec"));
+    assert_eq!(buffer.capacity(), capacity);
+}
@@ -0,0 +1,117 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytype::*;
+use crate::keytypes::{blacklist, dns_resolver, encrypted, logon, rxrpc, rxrpc_s, trusted};
+
+fn round_trip<D>(description: &str) -> Option<String>
+where
+    D: FromKeyDescription + KeyDescription,
+{
+    D::from_description(description).map(|desc| desc.description().into_owned())
+}
+
+#[test]
+fn test_description_round_trip() {
+    type RoundTrip = fn(&str) -> Option<String>;
+    let cases: &[(RoundTrip, &str, bool)] = &[
+        (round_trip::<blacklist::Description>, "tbs:deadbeef", true),
+        (round_trip::<blacklist::Description>, "tbs", false),
+        (round_trip::<blacklist::Description>, "tbs:xyz", false),
+        (round_trip::<dns_resolver::Description>, "example.com", true),
+        (
+            round_trip::<dns_resolver::Description>,
+            "aaaa:example.com",
+            true,
+        ),
+        (
+            round_trip::<dns_resolver::Description>,
+            "srv:_afs3-vlserver._udp.example.com",
+            true,
+        ),
+        (round_trip::<logon::Description>, "cifs:user:name", true),
+        (round_trip::<logon::Description>, "nosubtype", false),
+        (round_trip::<rxrpc_s::Description>, "52:2", true),
+        (round_trip::<rxrpc_s::Description>, "52", false),
+        (round_trip::<rxrpc_s::Description>, "65536:2", false),
+        (round_trip::<rxrpc_s::Description>, "52:256", false),
+    ];
+
+    for &(round_trip, description, valid) in cases {
+        let expected = if valid {
+            Some(description.into())
+        } else {
+            None
+        };
+        assert_eq!(round_trip(description), expected, "{}", description);
+    }
+}
+
+#[test]
+fn test_debug_redacted() {
+    let options = trusted::TrustedOptions {
+        keyhandle: Some(0x8100_0001),
+        keyauth: Some([0xab; 20].into()),
+        blobauth: Some([0xcd; 20].into()),
+        ..Default::default()
+    };
+    let cases = [
+        (
+            format!(
+                "{:?}",
+                encrypted::Payload::Load {
+                    blob: vec![222, 173, 190, 239],
+                },
+            ),
+            "Load { blob: <redacted> }",
+        ),
+        (
+            format!("{:?}", rxrpc::Payload::new(1, 2, [0xab; 8], vec![0xcd; 4])),
+            "Payload { expiry: 1, version: 2, session_key: <redacted>, ticket: <redacted> }",
+        ),
+        (
+            format!("{:?}", rxrpc_s::Payload::new([0xab; 8])),
+            "Payload { key: <redacted> }",
+        ),
+        (
+            format!(
+                "{:?}",
+                trusted::Payload::Load {
+                    blob: vec![222, 173, 190, 239],
+                    options,
+                },
+            ),
+            "Load { blob: <redacted>, options: TrustedOptions { keyhandle: Some(2164260865), \
+             keyauth: Some(TpmAuth(<redacted>)), blobauth: Some(TpmAuth(<redacted>)), \
+             pcrinfo: None, pcrlock: None, migratable: None, hash: None, policydigest: None, \
+             policyhandle: None } }",
+        ),
+    ];
+
+    for (debug, expected) in &cases {
+        assert_eq!(debug, expected);
+    }
+}
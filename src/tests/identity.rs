@@ -0,0 +1,177 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use keyutils_raw::KEY_SPEC_SESSION_KEYRING;
+
+use crate::keytypes::User;
+use crate::{Key, KeyHandle, KeyIdentity, Keyring, KeyringSerial, Operation};
+
+use super::utils;
+
+#[test]
+fn key_from_serial_checked() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("key_from_serial_checked", &b"payload"[..])
+        .unwrap();
+
+    let checked = Key::from_serial_checked(key.serial()).unwrap();
+    assert_eq!(checked, *key);
+
+    let err = Key::from_serial_checked(keyring.serial()).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EISDIR));
+}
+
+#[test]
+fn keyring_from_serial_checked() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("keyring_from_serial_checked", &b"payload"[..])
+        .unwrap();
+
+    let checked = Keyring::from_serial_checked(keyring.serial()).unwrap();
+    assert_eq!(checked, *keyring);
+
+    let err = Keyring::from_serial_checked(key.serial()).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOTDIR));
+}
+
+#[test]
+fn from_serial_checked_missing() {
+    let err = Key::from_serial_checked(utils::invalid_key().serial()).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+
+    let err = Keyring::from_serial_checked(utils::invalid_keyring().serial()).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn from_serial_checked_special() {
+    // Negative IDs are special keyrings, not serials.
+    let session = KeyringSerial::new(KEY_SPEC_SESSION_KEYRING.get()).unwrap();
+
+    let err = Key::from_serial_checked(session).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+    assert_eq!(err.operation(), Operation::CheckSerial);
+
+    let err = Keyring::from_serial_checked(session).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+    assert_eq!(err.operation(), Operation::CheckSerial);
+
+    // Unchecked handles are rejected when used as well.
+    let keyring = unsafe { Keyring::new(session) };
+    let err = keyring.description().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn pin_identity() {
+    let mut keyring = utils::new_test_keyring();
+    let mut key = keyring
+        .add_key::<User, _, _>("pin_identity", &b"payload"[..])
+        .unwrap()
+        .into_key();
+
+    assert!(key.identity().is_none());
+    key.pin_identity().unwrap();
+    let identity = key.identity().unwrap();
+    assert_eq!(identity.type_(), "user");
+    assert_eq!(identity.description(), "pin_identity");
+    assert_eq!(identity.uid(), unsafe { libc::getuid() });
+    assert_eq!(identity.in_quota(), Some(true));
+
+    key.verify_identity().unwrap();
+    key.update::<User, _>(&b"updated"[..]).unwrap();
+    assert_eq!(key.read().unwrap(), b"updated");
+
+    // Pinning does not affect equality.
+    let unpinned = utils::keyring_as_key(&keyring);
+    assert_ne!(key, unpinned);
+    assert_eq!(key, Key::from_serial_checked(key.serial()).unwrap());
+}
+
+#[test]
+fn stale_key() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("stale_key", &b"payload"[..])
+        .unwrap();
+    let other = keyring
+        .add_key::<User, _, _>("stale_key_other", &b"payload"[..])
+        .unwrap();
+
+    // Simulate the serial having been reused by pinning the identity of another key.
+    let mut stale = key.clone().into_key();
+    stale.set_identity(Some(KeyIdentity::capture(other.serial()).unwrap()));
+
+    let err = stale.verify_identity().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ESTALE));
+    let err = stale.update::<User, _>(&b"updated"[..]).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ESTALE));
+    let err = keyring.unlink_key(&stale).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ESTALE));
+    let err = stale.invalidate().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ESTALE));
+
+    assert_eq!(key.read().unwrap(), b"payload");
+}
+
+#[test]
+fn stale_keyring() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("stale_keyring", &b"payload"[..])
+        .unwrap();
+    let mut other = keyring.add_keyring("stale_keyring_other").unwrap();
+
+    let mut stale = other.clone();
+    stale.set_identity(Some(KeyIdentity::capture(keyring.serial()).unwrap()));
+
+    let err = stale.link_key(&key).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ESTALE));
+    let err = stale.clear().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ESTALE));
+
+    other.pin_identity().unwrap();
+    other.link_key(&key).unwrap();
+    let (keys, _) = other.read().unwrap();
+    assert_eq!(keys, [key.into_key()]);
+}
+
+#[test]
+fn chown_pinned() {
+    let mut keyring = utils::new_test_keyring();
+    let mut key = keyring
+        .add_key::<User, _, _>("chown_pinned", &b"payload"[..])
+        .unwrap()
+        .into_key();
+    key.pin_identity().unwrap();
+
+    let uid = unsafe { libc::getuid() };
+    key.chown(uid).unwrap();
+    assert_eq!(key.identity().unwrap().uid(), uid);
+    key.verify_identity().unwrap();
+}
@@ -0,0 +1,147 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::time::Duration;
+
+use crate::keytypes::{Keyring, User};
+use crate::{KeyFlags, KeyHandle, KeyTimeout, KeyType};
+
+use super::utils;
+use super::utils::kernel::*;
+
+#[test]
+fn invalid_key() {
+    let key = utils::invalid_key();
+    let err = key.info().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
+
+#[test]
+fn key_info() {
+    let mut keyring = utils::new_test_keyring();
+    let description = "key_info: with separator";
+    let mut key = keyring
+        .add_key::<User, _, _>(description, &b"payload"[..])
+        .unwrap();
+    key.set_timeout(Duration::from_secs(30)).unwrap();
+
+    let desc = key.description().unwrap();
+    let info = key.info().unwrap();
+    assert_eq!(info.serial, key.serial());
+    assert!(info.flags.contains(KeyFlags::INSTANTIATED));
+    assert!(!info.flags.contains(KeyFlags::REVOKED));
+    assert!(info.usage >= 1);
+    match info.timeout {
+        KeyTimeout::Remaining(remaining) => assert!(remaining <= Duration::from_secs(30)),
+        timeout => panic!("unexpected timeout: {:?}", timeout),
+    }
+    assert_eq!(info.perms, desc.perms);
+    assert_eq!(info.uid, *UID);
+    assert_eq!(info.gid, *GID);
+    assert_eq!(info.type_, User::name());
+    assert_eq!(info.description, description);
+    assert_eq!(info.summary.as_deref(), Some("7"));
+}
+
+#[test]
+fn keyring_info() {
+    let mut keyring = utils::new_test_keyring();
+    let keyring = keyring.add_keyring("keyring_info").unwrap();
+
+    let info = keyring.info().unwrap();
+    assert_eq!(info.serial, keyring.serial());
+    assert_eq!(info.timeout, KeyTimeout::Permanent);
+    assert_eq!(info.type_, Keyring::name());
+    assert_eq!(info.description, "keyring_info");
+    assert_eq!(info.summary.as_deref(), Some("empty"));
+}
+
+#[test]
+fn forged_entries() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("forged_entries", &b"payload"[..])
+        .unwrap();
+    let desc = key.description().unwrap();
+
+    // Descriptions are not escaped in `/proc/keys`, so they may inject lines.
+    let forged = format!(
+        "forged\n{:08x} I------     1 perm {:08x} {:5} {:5} logon     forged_entries: 1\nnot an entry",
+        key.serial().get(),
+        desc.perms.bits(),
+        desc.uid,
+        desc.gid,
+    );
+    keyring
+        .add_key::<User, _, _>(forged.as_str(), &b"forger"[..])
+        .unwrap();
+
+    let forged_infos = crate::KeyInfo::all()
+        .unwrap()
+        .into_iter()
+        .filter(|info| info.serial == key.serial())
+        .count();
+    assert_eq!(forged_infos, 2);
+
+    let info = key.info().unwrap();
+    assert_eq!(info.type_, User::name());
+    assert_eq!(info.description, "forged_entries");
+    assert_eq!(info.summary.as_deref(), Some("7"));
+}
+
+#[test]
+fn non_utf8_description() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("non_utf8_description", &b"payload"[..])
+        .unwrap();
+
+    // The API only accepts `str` descriptions, so add the key directly.
+    let payload = b"payload";
+    let serial = unsafe {
+        libc::syscall(
+            libc::SYS_add_key,
+            b"user\0".as_ptr(),
+            b"non_utf8_description: \xff\xfe\0".as_ptr(),
+            payload.as_ptr(),
+            payload.len(),
+            keyring.serial().get(),
+        )
+    };
+    assert!(serial > 0);
+
+    let infos = crate::KeyInfo::all().unwrap();
+    let info = infos
+        .iter()
+        .find(|info| info.serial.get() as libc::c_long == serial)
+        .unwrap();
+    assert_eq!(info.description, "non_utf8_description: \u{fffd}\u{fffd}");
+    assert_eq!(info.summary.as_deref(), Some("7"));
+    assert!(infos.iter().any(|info| info.serial == key.serial()));
+
+    let info = key.info().unwrap();
+    assert_eq!(info.description, "non_utf8_description");
+}
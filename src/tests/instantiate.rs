@@ -38,7 +38,7 @@ fn instantiate_invalid_key() {
 
     let payload = &b"payload"[..];
     let err = manager.instantiate(None, payload).unwrap_err();
-    assert_eq!(err, errno::Errno(libc::EPERM));
+    assert_eq!(err, errno::Errno(libc::EINVAL));
 }
 
 #[test]
@@ -49,7 +49,7 @@ fn reject_invalid_key() {
     let duration = Duration::from_secs(1);
     let errno = errno::Errno(libc::EKEYREJECTED);
     let err = manager.reject(None, duration, errno).unwrap_err();
-    assert_eq!(err, errno::Errno(libc::EPERM));
+    assert_eq!(err, errno::Errno(libc::EINVAL));
 }
 
 #[test]
@@ -59,7 +59,7 @@ fn negate_invalid_key() {
 
     let duration = Duration::from_secs(1);
     let err = manager.negate(None, duration).unwrap_err();
-    assert_eq!(err, errno::Errno(libc::EPERM));
+    assert_eq!(err, errno::Errno(libc::EINVAL));
 }
 
 #[test]
@@ -70,7 +70,7 @@ fn instantiate_into_not_key() {
         .add_key::<User, _, _>("instantiate_into_not_key", payload)
         .unwrap();
     let mut not_a_keyring = utils::key_as_keyring(&key);
-    let manager = KeyManager::test_new(key);
+    let manager = KeyManager::test_new(key.into_key());
 
     let payload = &b"payload"[..];
     let err = manager
@@ -88,7 +88,7 @@ fn reject_into_not_key() {
         .add_key::<User, _, _>("reject_into_not_key", payload)
         .unwrap();
     let mut not_a_keyring = utils::key_as_keyring(&key);
-    let manager = KeyManager::test_new(key);
+    let manager = KeyManager::test_new(key.into_key());
 
     let duration = Duration::from_secs(1);
     let errno = errno::Errno(libc::EKEYREJECTED);
@@ -107,7 +107,7 @@ fn negate_into_not_key() {
         .add_key::<User, _, _>("negate_into_not_key", payload)
         .unwrap();
     let mut not_a_keyring = utils::key_as_keyring(&key);
-    let manager = KeyManager::test_new(key);
+    let manager = KeyManager::test_new(key.into_key());
 
     let duration = Duration::from_secs(1);
     let err = manager.negate(&mut not_a_keyring, duration).unwrap_err();
@@ -122,7 +122,7 @@ fn instantiate_already_instantiated() {
     let key = keyring
         .add_key::<User, _, _>("instantiate_already_instantiated", payload)
         .unwrap();
-    let manager = KeyManager::test_new(key);
+    let manager = KeyManager::test_new(key.into_key());
 
     let err = manager.instantiate(None, payload).unwrap_err();
     assert_eq!(err, errno::Errno(libc::EPERM));
@@ -135,7 +135,7 @@ fn reject_already_instantiated() {
     let key = keyring
         .add_key::<User, _, _>("reject_already_instantiated", payload)
         .unwrap();
-    let manager = KeyManager::test_new(key);
+    let manager = KeyManager::test_new(key.into_key());
 
     let duration = Duration::from_secs(1);
     let errno = errno::Errno(libc::EKEYREJECTED);
@@ -150,7 +150,7 @@ fn negate_already_instantiated() {
     let key = keyring
         .add_key::<User, _, _>("negate_already_instantiated", payload)
         .unwrap();
-    let manager = KeyManager::test_new(key);
+    let manager = KeyManager::test_new(key.into_key());
 
     let duration = Duration::from_secs(1);
     let err = manager.negate(None, duration).unwrap_err();
@@ -168,7 +168,7 @@ fn instantiate_unlinked_key() {
     keyring.unlink_key(&key).unwrap();
     utils::wait_for_key_gc(&key);
 
-    let manager = KeyManager::test_new(key);
+    let manager = KeyManager::test_new(key.into_key());
 
     let err = manager.instantiate(None, payload).unwrap_err();
     assert_eq!(err, errno::Errno(libc::EPERM));
@@ -185,7 +185,7 @@ fn reject_unlinked_key() {
     keyring.unlink_key(&key).unwrap();
     utils::wait_for_key_gc(&key);
 
-    let manager = KeyManager::test_new(key);
+    let manager = KeyManager::test_new(key.into_key());
 
     let duration = Duration::from_secs(1);
     let errno = errno::Errno(libc::EKEYREJECTED);
@@ -204,7 +204,7 @@ fn negate_unlinked_key() {
     keyring.unlink_key(&key).unwrap();
     utils::wait_for_key_gc(&key);
 
-    let manager = KeyManager::test_new(key);
+    let manager = KeyManager::test_new(key.into_key());
 
     let duration = Duration::from_secs(1);
     let err = manager.negate(None, duration).unwrap_err();
@@ -48,3 +48,31 @@ fn test_key_is_keytype() {
     assert!(key.is_keytype::<User>().unwrap());
     assert!(!key.is_keytype::<Keyring>().unwrap());
 }
+
+#[test]
+fn test_key_downcast() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("test_key_downcast", payload)
+        .unwrap()
+        .into_key();
+
+    assert!(key.downcast::<Keyring>().unwrap().is_none());
+
+    let mut typed = key.downcast::<User>().unwrap().unwrap();
+    assert_eq!(typed, key);
+
+    let payload = &b"updated_payload"[..];
+    typed.update(payload).unwrap();
+    assert_eq!(typed.read().unwrap(), payload);
+}
+
+#[test]
+fn test_keyring_downcast() {
+    let keyring = utils::new_test_keyring();
+    let key = utils::keyring_as_key(&keyring);
+
+    assert!(key.downcast::<User>().unwrap().is_none());
+    assert!(key.downcast::<Keyring>().unwrap().is_some());
+}
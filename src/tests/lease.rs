@@ -0,0 +1,190 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::keytypes::User;
+use crate::{
+    Key, KeyHandle, KeyLease, KeyringSerial, Operation, Permission, Result, TimeoutRounding,
+};
+
+use super::utils;
+
+/// Wait for a lease to stop renewing on its own.
+fn wait_for_inactive(lease: &KeyLease) {
+    for _ in 0..100 {
+        if !lease.is_active() {
+            return;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    panic!("the lease did not stop renewing");
+}
+
+#[test]
+fn lease_renews() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("lease_renews", &b"payload"[..])
+        .unwrap();
+    // The renewal thread does not possess keys in the test thread's keyring.
+    key.clone()
+        .set_permissions(Permission::POSSESSOR_ALL | Permission::USER_ALL)
+        .unwrap();
+
+    // `/proc/keys` reports the remaining time in whole seconds below a minute. Renewing often
+    // keeps it within a second of the timeout while it falls behind without renewals.
+    let timeout = Duration::from_secs(50);
+    let lease = KeyLease::with_interval(key.clone(), timeout, Duration::from_millis(100)).unwrap();
+    assert!(lease.is_active());
+
+    thread::sleep(Duration::from_millis(1200));
+    let remaining = key.remaining_lifetime().unwrap().unwrap();
+    assert!(remaining >= Duration::from_secs(49));
+
+    lease.stop().unwrap();
+    thread::sleep(Duration::from_millis(2200));
+    let remaining = key.remaining_lifetime().unwrap().unwrap();
+    assert!(remaining <= Duration::from_secs(48));
+}
+
+#[test]
+fn lease_revoked() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("lease_revoked", &b"payload"[..])
+        .unwrap();
+    // The renewal thread does not possess keys in the test thread's keyring.
+    key.clone()
+        .set_permissions(Permission::POSSESSOR_ALL | Permission::USER_ALL)
+        .unwrap();
+
+    let lease = KeyLease::with_interval(
+        key.clone(),
+        Duration::from_secs(10),
+        Duration::from_millis(50),
+    )
+    .unwrap();
+    key.revoke().unwrap();
+
+    wait_for_inactive(&lease);
+    let err = lease.stop().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EKEYREVOKED));
+}
+
+/// A handle which panics when its timeout is renewed.
+#[derive(Debug)]
+struct PanickingHandle {
+    key: Key,
+    renewed: AtomicBool,
+}
+
+impl KeyHandle for PanickingHandle {
+    fn serial(&self) -> KeyringSerial {
+        self.key.serial()
+    }
+
+    fn set_timeout_rounded(&mut self, timeout: Duration, rounding: TimeoutRounding) -> Result<()> {
+        if self.renewed.swap(true, Ordering::SeqCst) {
+            panic!("renewal failed");
+        }
+        self.key.set_timeout_rounded(timeout, rounding)
+    }
+}
+
+#[test]
+fn lease_panicked() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("lease_panicked", &b"payload"[..])
+        .unwrap()
+        .into_key();
+
+    let handle = || {
+        PanickingHandle {
+            key: key.clone(),
+            renewed: AtomicBool::new(false),
+        }
+    };
+    let timeout = Duration::from_secs(10);
+    let interval = Duration::from_millis(50);
+
+    let lease = KeyLease::with_interval(handle(), timeout, interval).unwrap();
+    wait_for_inactive(&lease);
+    let err = lease.stop().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EIO));
+    assert_eq!(err.operation(), Operation::LeaseRenewal);
+
+    // Dropping the lease does not propagate the panic.
+    let lease = KeyLease::with_interval(handle(), timeout, interval).unwrap();
+    wait_for_inactive(&lease);
+    drop(lease);
+}
+
+#[test]
+fn lease_invalid_interval() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("lease_invalid_interval", &b"payload"[..])
+        .unwrap();
+
+    let timeout = Duration::from_secs(1);
+    let err = KeyLease::with_interval(key.clone(), timeout, timeout).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+    let err = KeyLease::with_interval(key.clone(), timeout, Duration::from_secs(0)).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+
+    // The key was not touched.
+    assert_eq!(key.remaining_lifetime().unwrap(), None);
+}
+
+#[test]
+fn lease_short_timeout() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("lease_short_timeout", &b"payload"[..])
+        .unwrap();
+    // The renewal thread does not possess keys in the test thread's keyring.
+    key.clone()
+        .set_permissions(Permission::POSSESSOR_ALL | Permission::USER_ALL)
+        .unwrap();
+
+    // The kernel may expire a key up to a second early, so a 1.5 second timeout is only
+    // guaranteed to last for a second.
+    let timeout = Duration::from_millis(1500);
+    let err = KeyLease::with_interval(key.clone(), timeout, Duration::from_secs(1)).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+    let err = KeyLease::new(key.clone(), Duration::from_secs(1)).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+
+    let lease = KeyLease::new(key.clone(), timeout).unwrap();
+    thread::sleep(Duration::from_secs(3));
+    assert!(lease.is_active());
+    assert_eq!(key.read().unwrap(), b"payload");
+    lease.stop().unwrap();
+}
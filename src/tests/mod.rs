@@ -30,16 +30,27 @@ pub(crate) mod utils;
 
 mod add;
 mod clear;
+mod create;
 mod describe;
+mod formatting;
+mod identity;
+mod info;
 mod instantiate;
 mod invalidate;
 mod keytype;
+mod lease;
 mod link;
+mod moving;
 mod newring;
 mod permitting;
 mod reading;
 mod revoke;
 mod search;
+mod specifier;
+mod sysctl;
+mod system;
 mod timeout;
 mod unlink;
 mod update;
+mod walk;
+mod watch;
@@ -0,0 +1,196 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytypes::User;
+use crate::MoveFlags;
+
+use super::utils;
+
+#[test]
+fn invalid_source() {
+    let mut invalid_keyring = utils::invalid_keyring();
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("invalid_source", payload)
+        .unwrap();
+
+    let err = invalid_keyring
+        .move_key(&key, &mut keyring, MoveFlags::empty())
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn invalid_destination() {
+    let mut invalid_keyring = utils::invalid_keyring();
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("invalid_destination", payload)
+        .unwrap();
+
+    let err = keyring
+        .move_key(&key, &mut invalid_keyring, MoveFlags::empty())
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn invalid_key() {
+    let mut keyring = utils::new_test_keyring();
+    let mut target_keyring = keyring.add_keyring("invalid_key_target").unwrap();
+    let invalid_key = utils::invalid_key();
+
+    let err = keyring
+        .move_key(&invalid_key, &mut target_keyring, MoveFlags::empty())
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn move_key_not_in_source() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("move_key_not_in_source", payload)
+        .unwrap();
+    let mut source_keyring = keyring.add_keyring("move_key_not_in_source_src").unwrap();
+    let mut target_keyring = keyring.add_keyring("move_key_not_in_source_dst").unwrap();
+
+    let err = source_keyring
+        .move_key(&key, &mut target_keyring, MoveFlags::empty())
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOENT));
+}
+
+#[test]
+fn move_key() {
+    let mut keyring = utils::new_test_keyring();
+    let mut target_keyring = keyring.add_keyring("move_key_target").unwrap();
+    let payload = &b"payload"[..];
+    let key = keyring.add_key::<User, _, _>("move_key", payload).unwrap();
+
+    keyring
+        .move_key(&key, &mut target_keyring, MoveFlags::empty())
+        .unwrap();
+
+    let (keys, keyrings) = keyring.read().unwrap();
+    assert!(keys.is_empty());
+    assert_eq!(1, keyrings.len());
+    assert_eq!(target_keyring, keyrings[0]);
+
+    let (keys, keyrings) = target_keyring.read().unwrap();
+    assert_eq!(1, keys.len());
+    assert_eq!(key, keys[0]);
+    assert!(keyrings.is_empty());
+}
+
+#[test]
+fn move_key_replace() {
+    let mut keyring = utils::new_test_keyring();
+    let mut target_keyring = keyring.add_keyring("move_key_replace_target").unwrap();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("move_key_replace", payload)
+        .unwrap();
+    let existing_key = target_keyring
+        .add_key::<User, _, _>("move_key_replace", payload)
+        .unwrap();
+    assert_ne!(key, existing_key);
+
+    keyring
+        .move_key(&key, &mut target_keyring, MoveFlags::empty())
+        .unwrap();
+
+    let (keys, keyrings) = target_keyring.read().unwrap();
+    assert_eq!(1, keys.len());
+    assert_eq!(key, keys[0]);
+    assert!(keyrings.is_empty());
+}
+
+#[test]
+fn move_key_exclusive() {
+    let mut keyring = utils::new_test_keyring();
+    let mut target_keyring = keyring.add_keyring("move_key_exclusive_target").unwrap();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("move_key_exclusive", payload)
+        .unwrap();
+    let existing_key = target_keyring
+        .add_key::<User, _, _>("move_key_exclusive", payload)
+        .unwrap();
+
+    let err = keyring
+        .move_key(&key, &mut target_keyring, MoveFlags::EXCLUSIVE)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EEXIST));
+
+    let (keys, _) = keyring.read().unwrap();
+    assert_eq!(1, keys.len());
+    assert_eq!(key, keys[0]);
+
+    let (keys, _) = target_keyring.read().unwrap();
+    assert_eq!(1, keys.len());
+    assert_eq!(existing_key, keys[0]);
+}
+
+#[test]
+fn move_keyring() {
+    let mut keyring = utils::new_test_keyring();
+    let mut target_keyring = keyring.add_keyring("move_keyring_target").unwrap();
+    let moved_keyring = keyring.add_keyring("move_keyring").unwrap();
+
+    keyring
+        .move_keyring(&moved_keyring, &mut target_keyring, MoveFlags::EXCLUSIVE)
+        .unwrap();
+
+    let (keys, keyrings) = keyring.read().unwrap();
+    assert!(keys.is_empty());
+    assert_eq!(1, keyrings.len());
+    assert_eq!(target_keyring, keyrings[0]);
+
+    let (keys, keyrings) = target_keyring.read().unwrap();
+    assert!(keys.is_empty());
+    assert_eq!(1, keyrings.len());
+    assert_eq!(moved_keyring, keyrings[0]);
+}
+
+#[test]
+fn move_keyring_into_self() {
+    let mut keyring = utils::new_test_keyring();
+    let mut target_keyring = keyring.add_keyring("move_keyring_into_self").unwrap();
+    let target_keyring_observer = target_keyring.clone();
+
+    let err = keyring
+        .move_keyring(
+            &target_keyring_observer,
+            &mut target_keyring,
+            MoveFlags::empty(),
+        )
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EDEADLK));
+}
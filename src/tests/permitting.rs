@@ -78,11 +78,10 @@ fn invalid_key_permissions() {
     let payload = &b"payload"[..];
     let mut key = keyring
         .add_key::<User, _, _>("invalid_key_permissions", payload)
-        .unwrap();
+        .unwrap()
+        .into_key();
 
-    let err = key
-        .set_permissions_raw(KeyPermissions::max_value())
-        .unwrap_err();
+    let err = key.set_permissions_raw(KeyPermissions::MAX).unwrap_err();
     assert_eq!(err, errno::Errno(libc::EINVAL));
 }
 
@@ -91,7 +90,7 @@ fn invalid_keyring_permissions() {
     let mut keyring = utils::new_test_keyring();
 
     let err = keyring
-        .set_permissions_raw(KeyPermissions::max_value())
+        .set_permissions_raw(KeyPermissions::MAX)
         .unwrap_err();
     assert_eq!(err, errno::Errno(libc::EINVAL));
 }
@@ -171,6 +170,7 @@ fn chown_key() {
             // Otherwise, we got the right error.
             Err(err) => assert_eq!(err, errno::Errno(libc::EACCES)),
         }
+    } else {
         let err = key.chown(1).unwrap_err();
         assert_eq!(err, errno::Errno(libc::EACCES));
     }
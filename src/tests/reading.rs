@@ -24,8 +24,8 @@
 // (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::keytypes::User;
-use crate::Permission;
+use crate::keytypes::{Keyring, User};
+use crate::{KeyringChild, Permission, SecretBuffer};
 
 use super::utils;
 
@@ -33,7 +33,7 @@ use super::utils;
 fn invalid_key() {
     let key = utils::invalid_key();
     let err = key.read().unwrap_err();
-    assert_eq!(err, errno::Errno(libc::ENOKEY));
+    assert_eq!(err, errno::Errno(libc::EINVAL));
 }
 
 #[test]
@@ -94,6 +94,19 @@ fn read_keyring() {
     assert!(keyrings.is_empty());
 }
 
+#[test]
+fn read_keyring_as_serials() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_keyring_as_serials", payload)
+        .unwrap();
+    let as_key = utils::keyring_as_key(&keyring);
+
+    let children = as_key.read_as::<Keyring>().unwrap();
+    assert_eq!(children, [key.serial()]);
+}
+
 #[test]
 fn read_key_as_keyring() {
     let mut keyring = utils::new_test_keyring();
@@ -187,3 +200,144 @@ fn reinstated_read_perm() {
     let actual_payload = key.read().unwrap();
     assert_eq!(payload, actual_payload.as_slice());
 }
+
+#[test]
+fn read_keyring_children() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_keyring_children", payload)
+        .unwrap();
+    let new_keyring = keyring.add_keyring("read_keyring_children").unwrap();
+
+    for &batch in &[false, true] {
+        let mut children = keyring.children().unwrap();
+        assert_eq!(children.len(), 2);
+        if batch {
+            children.batch_classify().unwrap();
+        }
+
+        let mut found_key = false;
+        let mut found_keyring = false;
+        for child in children {
+            match child.classify().unwrap() {
+                KeyringChild::Key(child_key) => {
+                    assert_eq!(child_key, *key);
+                    assert_eq!(child.serial(), key.serial());
+                    found_key = true;
+                },
+                KeyringChild::Keyring(child_keyring) => {
+                    assert_eq!(child_keyring, new_keyring);
+                    assert_eq!(child.description().unwrap().type_, "keyring");
+                    found_keyring = true;
+                },
+            }
+        }
+        assert!(found_key);
+        assert!(found_keyring);
+    }
+}
+
+#[test]
+fn read_large_keyring() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    for i in 0..20 {
+        keyring
+            .add_key::<User, _, _>(format!("read_large_keyring{}", i), payload)
+            .unwrap();
+        keyring
+            .add_keyring(format!("read_large_keyring{}", i))
+            .unwrap();
+    }
+
+    let (keys, keyrings) = keyring.read().unwrap();
+    assert_eq!(keys.len(), 20);
+    assert_eq!(keyrings.len(), 20);
+    for key in keys {
+        assert_eq!(key.description().unwrap().type_, "user");
+    }
+    for keyring in keyrings {
+        assert_eq!(keyring.description().unwrap().type_, "keyring");
+    }
+}
+
+#[test]
+fn read_key_into() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_key_into", payload)
+        .unwrap();
+
+    let mut buffer = [0; 16];
+    let sz = key.read_into(&mut buffer).unwrap();
+    assert_eq!(&buffer[..sz], payload);
+
+    let mut small_buffer = [0; 4];
+    let sz = key.read_into(&mut small_buffer).unwrap();
+    assert_eq!(sz, payload.len());
+}
+
+#[test]
+fn read_key_into_vec() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_key_into_vec", payload)
+        .unwrap();
+    let long_payload = &b"a much longer payload"[..];
+    let long_key = keyring
+        .add_key::<User, _, _>("read_key_into_vec_long", long_payload)
+        .unwrap();
+
+    let mut buffer = Vec::new();
+    assert_eq!(key.read_into_vec(&mut buffer).unwrap(), payload.len());
+    assert_eq!(buffer, payload);
+
+    // The buffer grows as needed.
+    assert_eq!(
+        long_key.read_into_vec(&mut buffer).unwrap(),
+        long_payload.len(),
+    );
+    assert_eq!(buffer, long_payload);
+
+    // The existing allocation is reused.
+    let capacity = buffer.capacity();
+    assert_eq!(key.read_into_vec(&mut buffer).unwrap(), payload.len());
+    assert_eq!(buffer, payload);
+    assert_eq!(buffer.capacity(), capacity);
+}
+
+#[test]
+fn read_key_secret() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = SecretBuffer::from_slice(b"payload");
+    let key = keyring
+        .add_key::<User, _, _>("read_key_secret", &payload)
+        .unwrap();
+
+    let secret = key.read_secret().unwrap();
+    assert_eq!(secret, payload);
+    assert_eq!(secret.capacity(), payload.len());
+}
+
+#[test]
+fn read_key_secret_into() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("read_key_secret_into", payload)
+        .unwrap();
+
+    let mut buffer = SecretBuffer::new(32);
+    assert_eq!(key.read_secret_into(&mut buffer).unwrap(), payload.len());
+    assert_eq!(&*buffer, payload);
+
+    let mut small_buffer = SecretBuffer::new(4);
+    assert_eq!(
+        key.read_secret_into(&mut small_buffer).unwrap(),
+        payload.len(),
+    );
+    assert_eq!(small_buffer.len(), 4);
+}
@@ -27,6 +27,7 @@
 use std::time::Duration;
 
 use crate::keytypes::User;
+use crate::{ErrorKind, Operation};
 
 use super::utils;
 
@@ -72,6 +73,9 @@ fn revoked_key() {
 
     let err = key_observer.description().unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYREVOKED));
+    assert_eq!(err.kind(), ErrorKind::Revoked);
+    assert_eq!(err.operation(), Operation::Describe);
+    assert_eq!(err.serial(), Some(key_observer.serial()));
 
     let err = key_observer.read().unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYREVOKED));
@@ -25,7 +25,7 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::keytypes::User;
-use crate::Permission;
+use crate::{ErrorKind, Key, KeyType, Operation, Permission};
 
 use super::utils;
 use super::utils::kernel::*;
@@ -139,6 +139,10 @@ fn search_key_no_result() {
         .search_for_key::<User, _, _>("search_key_no_result", None)
         .unwrap_err();
     assert_eq!(err, errno::Errno(libc::ENOKEY));
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert_eq!(err.operation(), Operation::Search);
+    assert_eq!(err.serial(), Some(keyring.serial()));
+    assert_eq!(err.key_type(), Some(User::name()));
 }
 
 #[test]
@@ -699,3 +703,80 @@ fn search_and_find_keyring_no_write_perm() {
     assert!(keys.is_empty());
     assert!(keyrings.is_empty());
 }
+
+#[test]
+fn search_and_find_key_dyn() {
+    let mut keyring = utils::new_test_keyring();
+    let description = "search_and_find_key_dyn";
+    let key = keyring
+        .add_key_dyn("user", description, b"payload")
+        .unwrap();
+
+    let found_key = keyring
+        .search_for_key_dyn("user", description, None)
+        .unwrap();
+    assert_eq!(found_key, key);
+
+    let err = keyring
+        .search_for_key_dyn("logon", description, None)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+    assert_eq!(err.key_type(), Some("logon"));
+}
+
+#[test]
+fn find_possessed_key() {
+    let mut keyring = utils::new_test_keyring();
+    let mut new_keyring = keyring.add_keyring("new_keyring").unwrap();
+    let description = "find_possessed_key";
+    let key = keyring
+        .add_key::<User, _, _>(description, &b"payload"[..])
+        .unwrap();
+
+    let found_key = Key::find::<User, _, _>(description, &mut new_keyring).unwrap();
+    assert_eq!(found_key, key);
+
+    let (keys, _) = new_keyring.read().unwrap();
+    assert_eq!(keys, [key.into_key()]);
+}
+
+#[test]
+fn find_unpossessed_key() {
+    let mut keyring = utils::new_test_keyring();
+    let mut new_keyring = keyring.add_keyring("new_keyring").unwrap();
+    let mut hidden_keyring = keyring.add_keyring("hidden_keyring").unwrap();
+    let description = "find_unpossessed_key";
+    let mut key = hidden_keyring
+        .add_key::<User, _, _>(description, &b"payload"[..])
+        .unwrap();
+    key.set_permissions(Permission::POSSESSOR_ALL | Permission::USER_VIEW | Permission::USER_LINK)
+        .unwrap();
+    // Without `search` permission, the key is no longer possessed.
+    hidden_keyring
+        .set_permissions(Permission::POSSESSOR_ALL - Permission::POSSESSOR_SEARCH)
+        .unwrap();
+
+    let err = keyring
+        .search_for_key::<User, _, _>(description, None)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+
+    let found_key = Key::find_dyn("user", description, &mut new_keyring).unwrap();
+    assert_eq!(found_key, key);
+
+    let (keys, _) = new_keyring.read().unwrap();
+    assert_eq!(keys, [found_key]);
+}
+
+#[test]
+fn find_missing_key() {
+    let mut keyring = utils::new_test_keyring();
+    let description = "find_missing_key";
+    keyring
+        .add_key::<User, _, _>(description, &b"payload"[..])
+        .unwrap();
+
+    let err = Key::find_dyn("logon", description, None).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+    assert_eq!(err.key_type(), Some("logon"));
+}
@@ -0,0 +1,86 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytypes::User;
+use crate::{KeyHandle, KeySpecifier, Keyring, SpecialKeyring};
+
+use super::utils;
+
+#[test]
+fn resolve_special() {
+    let session = Keyring::attach_or_create(SpecialKeyring::Session).unwrap();
+
+    let spec: KeySpecifier = "@s".parse().unwrap();
+    assert_eq!(spec.resolve_keyring().unwrap(), session);
+    assert_eq!(spec.resolve_keyring_or_create().unwrap(), session);
+
+    let err = spec.resolve_key().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EISDIR));
+}
+
+#[test]
+fn resolve_serial() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("resolve_serial", &b"payload"[..])
+        .unwrap();
+
+    let spec: KeySpecifier = key.serial().to_string().parse().unwrap();
+    assert_eq!(spec.resolve_key().unwrap(), *key);
+    let err = spec.resolve_keyring().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOTDIR));
+
+    let spec: KeySpecifier = format!("{:#x}", keyring.serial()).parse().unwrap();
+    assert_eq!(spec.resolve_keyring().unwrap(), *keyring);
+}
+
+#[test]
+fn resolve_lookup() {
+    let mut keyring = utils::new_test_keyring();
+    let key = keyring
+        .add_key::<User, _, _>("resolve_lookup:key", &b"payload"[..])
+        .unwrap();
+    let subring = keyring.add_keyring("resolve_lookup_ring").unwrap();
+
+    let spec: KeySpecifier = "%user:resolve_lookup:key".parse().unwrap();
+    assert_eq!(spec.resolve_key().unwrap(), *key);
+
+    let spec: KeySpecifier = "%:resolve_lookup_ring".parse().unwrap();
+    assert_eq!(spec.resolve_keyring().unwrap(), subring);
+    let err = spec.resolve_key().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EISDIR));
+
+    let spec: KeySpecifier = "%user:resolve_lookup_missing".parse().unwrap();
+    let err = spec.resolve_key().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
+
+#[test]
+fn resolve_request_key_auth_missing() {
+    let spec: KeySpecifier = "@a".parse().unwrap();
+    let err = spec.resolve_key().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
@@ -0,0 +1,70 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::{KernelFeatures, KeySysctl};
+
+use super::utils;
+use super::utils::kernel::*;
+
+#[test]
+fn read_sysctl() {
+    let sysctl = KeySysctl::read().unwrap();
+
+    let (maxkeys, maxbytes) = if *UID == 0 {
+        (sysctl.root_maxkeys, sysctl.root_maxbytes)
+    } else {
+        (sysctl.maxkeys, sysctl.maxbytes)
+    };
+    assert_eq!(KEY_INFO.maxkeys, maxkeys);
+    assert_eq!(KEY_INFO.maxbytes, maxbytes);
+}
+
+#[test]
+fn write_sysctl() {
+    let sysctl = KeySysctl::read().unwrap();
+
+    // Write back the current value so that the system is not changed.
+    let res = KeySysctl::set_gc_delay(sysctl.gc_delay);
+    if *UID == 0 {
+        res.unwrap();
+    } else {
+        assert_eq!(res.unwrap_err(), errno::Errno(libc::EACCES));
+    }
+}
+
+#[test]
+fn attach_persistent_expiry() {
+    if !KernelFeatures::probe().unwrap().persistent_keyrings {
+        return;
+    }
+
+    let mut keyring = utils::new_test_keyring();
+    let (persistent, expiry) = keyring.attach_persistent_with_expiry().unwrap();
+    assert_eq!(expiry, KeySysctl::read().unwrap().persistent_keyring_expiry,);
+
+    let (_, keyrings) = keyring.read().unwrap();
+    assert_eq!(keyrings, [persistent]);
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytypes::User;
+use crate::{Keyring, SystemKeyring};
+
+use super::utils;
+
+#[test]
+fn attach_system_keyrings() {
+    for &id in SystemKeyring::ALL {
+        match Keyring::attach_system(id) {
+            Ok(keyring) => {
+                let desc = keyring.description().unwrap();
+                assert_eq!(desc.type_, "keyring");
+                assert_eq!(desc.description, id.name());
+            },
+            Err(err) => assert_eq!(err, errno::Errno(libc::ENOKEY)),
+        }
+    }
+}
+
+#[test]
+fn attach_forged_system_keyrings() {
+    let mut keyring = utils::new_test_keyring();
+    let forged_keyring = keyring
+        .add_keyring("attach_forged_system_keyrings")
+        .unwrap();
+
+    // Descriptions are not escaped in `/proc/keys`, so they may inject lines.
+    for &id in SystemKeyring::ALL {
+        let forged = format!(
+            "forged\n{:08x} I------     1 perm 3f030000     0     0 keyring   {}: 1",
+            forged_keyring.serial().get(),
+            id.name(),
+        );
+        keyring
+            .add_key::<User, _, _>(forged.as_str(), &b"forger"[..])
+            .unwrap();
+    }
+
+    for &id in SystemKeyring::ALL {
+        match Keyring::attach_system(id) {
+            Ok(keyring) => assert_ne!(keyring, forged_keyring),
+            Err(err) => assert_eq!(err, errno::Errno(libc::ENOKEY)),
+        }
+    }
+}
+
+#[test]
+fn list_system_certificates() {
+    for &(id, types) in &[
+        (SystemKeyring::BuiltinTrusted, &["asymmetric"][..]),
+        (SystemKeyring::Blacklist, &["asymmetric", "blacklist"][..]),
+    ] {
+        let keys = match Keyring::system_certificates(id) {
+            Ok(keys) => keys,
+            Err(err) if err == errno::Errno(libc::ENOKEY) => continue,
+            Err(err) => panic!("failed to list {}: {}", id.name(), err),
+        };
+
+        for key in keys {
+            let desc = key.description().unwrap();
+            assert!(types.contains(&desc.type_.as_str()));
+        }
+    }
+}
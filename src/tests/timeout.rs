@@ -25,9 +25,10 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use crate::keytypes::User;
+use crate::{KeyHandle, TimeoutRounding};
 
 use super::utils;
 
@@ -144,3 +145,56 @@ fn expired_keyring() {
     let err = keyring_observer.revoke().unwrap_err();
     assert_eq!(err, errno::Errno(libc::EKEYEXPIRED));
 }
+
+#[test]
+fn subsecond_timeout() {
+    let mut keyring = utils::new_test_keyring();
+    let mut key = keyring
+        .add_key::<User, _, _>("subsecond_timeout", &b"payload"[..])
+        .unwrap();
+    assert_eq!(key.remaining_lifetime().unwrap(), None);
+
+    // A partial second must not turn into "no expiration".
+    key.set_timeout(Duration::from_millis(900)).unwrap();
+    let remaining = key.remaining_lifetime().unwrap().unwrap();
+    assert!(remaining <= Duration::from_secs(1));
+
+    key.set_timeout_rounded(Duration::from_millis(2100), TimeoutRounding::Up)
+        .unwrap();
+    let remaining = key.remaining_lifetime().unwrap().unwrap();
+    assert!(remaining > Duration::from_secs(1));
+    assert!(remaining <= Duration::from_secs(3));
+}
+
+#[test]
+fn expiry_key() {
+    let mut keyring = utils::new_test_keyring();
+    let mut key = keyring
+        .add_key::<User, _, _>("expiry_key", &b"payload"[..])
+        .unwrap();
+
+    key.set_expiry(SystemTime::now() + Duration::from_secs(50))
+        .unwrap();
+    let remaining = key.remaining_lifetime().unwrap().unwrap();
+    assert!(remaining <= Duration::from_secs(50));
+    assert!(remaining >= Duration::from_secs(40));
+}
+
+#[test]
+fn past_expiry_key() {
+    let mut keyring = utils::new_test_keyring();
+    let mut key = keyring
+        .add_key::<User, _, _>("past_expiry_key", &b"payload"[..])
+        .unwrap();
+
+    key.set_expiry(SystemTime::now() - Duration::from_secs(100))
+        .unwrap();
+    thread::sleep(Duration::from_secs(2));
+
+    let err = key.read().unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EKEYEXPIRED));
+    assert_eq!(
+        key.remaining_lifetime().unwrap(),
+        Some(Duration::from_secs(0)),
+    );
+}
@@ -59,7 +59,7 @@ fn unlinked_key() {
     utils::wait_for_key_gc(&key);
 
     let payload = &b"payload"[..];
-    let err = key.update::<User, _>(payload).unwrap_err();
+    let err = key.update(payload).unwrap_err();
     assert_eq!(err, errno::Errno(libc::ENOKEY));
 }
 
@@ -73,8 +73,29 @@ fn user_key() {
     assert_eq!(payload, actual_payload.as_slice());
 
     let payload = &b"updated_payload"[..];
-    key.update::<User, _>(payload).unwrap();
+    key.update(payload).unwrap();
 
     let actual_payload = key.read().unwrap();
     assert_eq!(payload, actual_payload.as_slice());
 }
+
+#[test]
+fn overlong_payload() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("overlong_payload", payload)
+        .unwrap();
+
+    // Updates are limited to a page even if the key type allows larger payloads.
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let payload = vec![0; page_size + 1];
+    if payload.len() > 32767 {
+        return;
+    }
+    let err = key.update(payload).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+
+    // The key is untouched.
+    assert_eq!(key.read().unwrap(), b"payload");
+}
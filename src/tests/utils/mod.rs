@@ -78,11 +78,11 @@ pub fn new_test_keyring() -> ScopedKeyring {
     }
 }
 
-unsafe fn invalid_serial() -> KeyringSerial {
-    // Yes, we're explicitly breaking the NonZeroI32 rules here. However, it is not passing through
-    // any bits which care (e.g., `Option`), so this is purely to test that using an invalid
-    // keyring ID gives back `EINVAL` as expected.
-    KeyringSerial::new_unchecked(0)
+fn invalid_serial() -> KeyringSerial {
+    // Serials are always positive and negative IDs are rejected with `EINVAL` before reaching the
+    // kernel, just as the kernel rejects an ID of 0. Since `KeyringSerial` may not be 0, use a
+    // negative ID to test that using an invalid keyring ID gives back `EINVAL` as expected.
+    KeyringSerial::new(-1000).unwrap()
 }
 
 pub fn invalid_keyring() -> Keyring {
@@ -105,7 +105,7 @@ pub fn key_as_keyring(key: &Key) -> Keyring {
 /// collected.
 pub fn wait_for_key_gc(key: &Key) {
     loop {
-        match key.description() {
+        match key.description().map_err(|err| err.errno()) {
             Ok(_) => (),
             Err(errno::Errno(libc::ENOKEY)) => break,
             e @ Err(_) => {
@@ -120,7 +120,7 @@ pub fn wait_for_key_gc(key: &Key) {
 /// collected.
 pub fn wait_for_keyring_gc(keyring: &Keyring) {
     loop {
-        match keyring.read() {
+        match keyring.read().map_err(|err| err.errno()) {
             Ok(_) | Err(errno::Errno(libc::EACCES)) => (),
             Err(errno::Errno(libc::ENOKEY)) => break,
             e @ Err(_) => {
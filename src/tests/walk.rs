@@ -0,0 +1,221 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytypes::User;
+use crate::{Keyring, KeyringChild, Permission, WalkAction, WalkOrder};
+
+use super::utils;
+
+fn item_description(item: &KeyringChild) -> String {
+    match item {
+        KeyringChild::Key(key) => key.description(),
+        KeyringChild::Keyring(keyring) => keyring.description(),
+    }
+    .unwrap()
+    .description
+}
+
+// Builds the following tree:
+//
+//   keyring
+//   ├── key_a
+//   ├── ring1
+//   │   ├── key_b
+//   │   └── ring2
+//   │       ├── key_c
+//   │       └── ring3 (also linked from the root)
+//   └── ring3
+//       └── key_d
+fn make_tree(keyring: &mut Keyring) {
+    let payload = &b"payload"[..];
+    keyring.add_key::<User, _, _>("key_a", payload).unwrap();
+    let mut ring1 = keyring.add_keyring("ring1").unwrap();
+    ring1.add_key::<User, _, _>("key_b", payload).unwrap();
+    let mut ring2 = ring1.add_keyring("ring2").unwrap();
+    ring2.add_key::<User, _, _>("key_c", payload).unwrap();
+    let mut ring3 = keyring.add_keyring("ring3").unwrap();
+    ring3.add_key::<User, _, _>("key_d", payload).unwrap();
+    ring2.link_keyring(&ring3).unwrap();
+}
+
+fn walk_tree(keyring: &Keyring, order: WalkOrder) -> Vec<(String, usize)> {
+    let mut visited = Vec::new();
+    let skipped = keyring
+        .walk(order, |entry| {
+            visited.push((item_description(&entry.item), entry.depth()));
+            WalkAction::Continue
+        })
+        .unwrap();
+    assert!(skipped.is_empty());
+    visited
+}
+
+fn assert_visited(visited: &[(String, usize)]) {
+    let mut names = visited
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(
+        names,
+        ["key_a", "key_b", "key_c", "key_d", "ring1", "ring2", "ring3"],
+    );
+}
+
+#[test]
+fn walk_depth_first() {
+    let mut keyring = utils::new_test_keyring();
+    make_tree(&mut keyring);
+
+    let visited = walk_tree(&keyring, WalkOrder::DepthFirst);
+    assert_visited(&visited);
+
+    // Each keyring is immediately followed by its contents.
+    let ring2 = visited
+        .iter()
+        .position(|(name, _)| name == "ring2")
+        .unwrap();
+    assert_eq!(visited[ring2 + 1], ("key_c".into(), 3));
+    let ring3 = visited
+        .iter()
+        .position(|(name, _)| name == "ring3")
+        .unwrap();
+    assert_eq!(visited[ring3 + 1].0, "key_d");
+    assert_eq!(visited[ring3 + 1].1, visited[ring3].1 + 1);
+}
+
+#[test]
+fn walk_breadth_first() {
+    let mut keyring = utils::new_test_keyring();
+    make_tree(&mut keyring);
+
+    let visited = walk_tree(&keyring, WalkOrder::BreadthFirst);
+    assert_visited(&visited);
+
+    assert!(visited.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    assert!(visited.contains(&("ring3".into(), 1)));
+    assert!(visited.contains(&("key_d".into(), 2)));
+}
+
+#[test]
+fn walk_path() {
+    let mut keyring = utils::new_test_keyring();
+    make_tree(&mut keyring);
+
+    let mut found = false;
+    keyring
+        .walk(WalkOrder::DepthFirst, |entry| {
+            if item_description(&entry.item) == "key_c" {
+                let path = entry
+                    .path
+                    .iter()
+                    .map(|keyring| keyring.description().unwrap().description)
+                    .collect::<Vec<_>>();
+                assert_eq!(path[1..], ["ring1", "ring2"]);
+                assert_eq!(*entry.parent(), entry.path[2]);
+                found = true;
+            }
+            WalkAction::Continue
+        })
+        .unwrap();
+    assert!(found);
+}
+
+#[test]
+fn walk_prune() {
+    let mut keyring = utils::new_test_keyring();
+    make_tree(&mut keyring);
+
+    let mut visited = Vec::new();
+    keyring
+        .walk(WalkOrder::DepthFirst, |entry| {
+            let name = item_description(&entry.item);
+            let action = if name == "ring1" {
+                WalkAction::Prune
+            } else {
+                WalkAction::Continue
+            };
+            visited.push(name);
+            action
+        })
+        .unwrap();
+    visited.sort_unstable();
+    assert_eq!(visited, ["key_a", "key_d", "ring1", "ring3"]);
+}
+
+#[test]
+fn walk_stop() {
+    let mut keyring = utils::new_test_keyring();
+    make_tree(&mut keyring);
+
+    for &order in &[WalkOrder::DepthFirst, WalkOrder::BreadthFirst] {
+        let mut count = 0;
+        keyring
+            .walk(order, |_| {
+                count += 1;
+                if count == 2 {
+                    WalkAction::Stop
+                } else {
+                    WalkAction::Continue
+                }
+            })
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+}
+
+#[test]
+fn walk_unreadable_keyring() {
+    let mut keyring = utils::new_test_keyring();
+    let mut ring = keyring.add_keyring("unreadable").unwrap();
+    ring.add_key::<User, _, _>("hidden", &b"payload"[..])
+        .unwrap();
+    // Possessed keyrings may be read with only the `search` permission.
+    ring.set_permissions(Permission::POSSESSOR_VIEW | Permission::USER_VIEW)
+        .unwrap();
+
+    let mut visited = Vec::new();
+    let skipped = keyring
+        .walk(WalkOrder::DepthFirst, |entry| {
+            visited.push(item_description(&entry.item));
+            WalkAction::Continue
+        })
+        .unwrap();
+    assert_eq!(visited, ["unreadable"]);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].keyring, ring);
+    assert_eq!(skipped[0].path, [keyring.clone()]);
+    assert_eq!(skipped[0].error, errno::Errno(libc::EACCES));
+}
+
+#[test]
+fn walk_invalid_keyring() {
+    let keyring = utils::invalid_keyring();
+    let err = keyring
+        .walk(WalkOrder::DepthFirst, |_| WalkAction::Continue)
+        .unwrap_err();
+    assert_eq!(err, errno::Errno(libc::ENOKEY));
+}
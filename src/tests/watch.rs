@@ -0,0 +1,168 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::keytypes::User;
+use crate::{KeyEvent, KeyNotification, KeyWatcher};
+
+use super::utils;
+
+fn new_watcher() -> Option<KeyWatcher> {
+    match KeyWatcher::new().map_err(|err| err.errno()) {
+        Ok(watcher) => Some(watcher),
+        Err(errno::Errno(libc::ENOPKG)) => {
+            eprintln!("This kernel does not support key notifications. Skipping this test.");
+            None
+        },
+        Err(err) => panic!("failed to create a key watcher: {}", err),
+    }
+}
+
+#[test]
+fn invalid_key() {
+    let mut watcher = match new_watcher() {
+        Some(watcher) => watcher,
+        None => return,
+    };
+    let key = utils::invalid_key();
+    let err = watcher.watch_key(&key, 1).unwrap_err();
+    assert_eq!(err, errno::Errno(libc::EINVAL));
+}
+
+#[test]
+fn watch_key() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring.add_key::<User, _, _>("watch_key", payload).unwrap();
+
+    let mut watcher = match new_watcher() {
+        Some(watcher) => watcher,
+        None => return,
+    };
+    watcher.watch_key(&key, 1).unwrap();
+
+    key.update(&b"updated"[..]).unwrap();
+    let notifications = watcher.read().unwrap();
+    assert_eq!(
+        notifications,
+        [KeyNotification::Key {
+            watch_id: 1,
+            subject: key.serial(),
+            event: KeyEvent::Updated,
+        }],
+    );
+
+    let key_observer = key.clone();
+    key.revoke().unwrap();
+    let notifications = watcher.read().unwrap();
+    assert_eq!(
+        notifications,
+        [KeyNotification::Key {
+            watch_id: 1,
+            subject: key_observer.serial(),
+            event: KeyEvent::Revoked,
+        }],
+    );
+}
+
+#[test]
+fn watch_keyring() {
+    let mut keyring = utils::new_test_keyring();
+
+    let mut watcher = match new_watcher() {
+        Some(watcher) => watcher,
+        None => return,
+    };
+    watcher.watch_keyring(&keyring, 2).unwrap();
+
+    let payload = &b"payload"[..];
+    let key = keyring
+        .add_key::<User, _, _>("watch_keyring", payload)
+        .unwrap();
+    keyring.unlink_key(&key).unwrap();
+    let other_key = keyring
+        .add_key::<User, _, _>("watch_keyring_other", payload)
+        .unwrap();
+    keyring.clear().unwrap();
+
+    let notifications = watcher.read().unwrap();
+    assert_eq!(
+        notifications,
+        [
+            KeyNotification::Key {
+                watch_id: 2,
+                subject: keyring.serial(),
+                event: KeyEvent::Linked {
+                    key: key.serial(),
+                },
+            },
+            KeyNotification::Key {
+                watch_id: 2,
+                subject: keyring.serial(),
+                event: KeyEvent::Unlinked {
+                    key: key.serial(),
+                },
+            },
+            KeyNotification::Key {
+                watch_id: 2,
+                subject: keyring.serial(),
+                event: KeyEvent::Linked {
+                    key: other_key.serial(),
+                },
+            },
+            KeyNotification::Key {
+                watch_id: 2,
+                subject: keyring.serial(),
+                event: KeyEvent::Cleared,
+            },
+        ],
+    );
+}
+
+#[test]
+fn unwatch_key() {
+    let mut keyring = utils::new_test_keyring();
+    let payload = &b"payload"[..];
+    let mut key = keyring
+        .add_key::<User, _, _>("unwatch_key", payload)
+        .unwrap();
+
+    let mut watcher = match new_watcher() {
+        Some(watcher) => watcher,
+        None => return,
+    };
+    watcher.watch_key(&key, 3).unwrap();
+    watcher.unwatch_key(&key).unwrap();
+
+    key.update(&b"updated"[..]).unwrap();
+    let notifications = watcher.read().unwrap();
+    assert_eq!(
+        notifications,
+        [KeyNotification::WatchRemoved {
+            watch_id: 3,
+            subject: Some(key.serial()),
+        }],
+    );
+}
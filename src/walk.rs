@@ -0,0 +1,221 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::collections::{HashSet, VecDeque};
+
+use keyutils_raw::KeyringSerial;
+
+use crate::api::{Key, Keyring, KeyringChild};
+use crate::error::{Error, Result};
+
+/// The order in which `Keyring::walk` visits keyrings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkOrder {
+    /// Descend into each keyring as soon as it is visited.
+    DepthFirst,
+    /// Visit all keyrings at one depth before descending further.
+    BreadthFirst,
+}
+
+/// An object visited while walking a keyring.
+#[derive(Debug)]
+pub struct WalkEntry<'a> {
+    /// The object which was found.
+    pub item: KeyringChild,
+    /// The keyrings leading to the object, starting with the keyring being walked.
+    pub path: &'a [Keyring],
+}
+
+impl<'a> WalkEntry<'a> {
+    /// The depth of the object.
+    ///
+    /// Direct children of the keyring being walked have a depth of 1.
+    pub fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    /// The keyring which contains the object.
+    pub fn parent(&self) -> &'a Keyring {
+        self.path.last().expect("walk entries always have a parent")
+    }
+}
+
+/// What to do after visiting an object while walking a keyring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkAction {
+    /// Continue walking; keyrings are descended into.
+    Continue,
+    /// Do not descend into the visited keyring.
+    ///
+    /// This is the same as `Continue` for keys.
+    Prune,
+    /// Stop walking.
+    Stop,
+}
+
+/// A keyring which could not be read while walking.
+#[derive(Debug, Clone)]
+pub struct SkippedKeyring {
+    /// The keyring which was skipped.
+    pub keyring: Keyring,
+    /// The keyrings leading to the skipped keyring, starting with the keyring being walked.
+    pub path: Vec<Keyring>,
+    /// Why the keyring could not be read.
+    pub error: Error,
+}
+
+struct Walker<F> {
+    visitor: F,
+    visited: HashSet<KeyringSerial>,
+    skipped: Vec<SkippedKeyring>,
+    stopped: bool,
+}
+
+impl<F> Walker<F>
+where
+    F: FnMut(&WalkEntry) -> WalkAction,
+{
+    /// Read the last keyring in `path`.
+    ///
+    /// Returns `None` if the keyring was skipped.
+    fn read(&mut self, path: &[Keyring]) -> Result<Option<(Vec<Key>, Vec<Keyring>)>> {
+        let (keyring, parents) = path.split_last().expect("paths are never empty");
+        match keyring.read() {
+            Ok(children) => Ok(Some(children)),
+            // The keyring being walked must be readable.
+            Err(err) if parents.is_empty() => Err(err),
+            // The keyring has disappeared since it was found.
+            Err(err) if err == errno::Errno(libc::ENOKEY) => Ok(None),
+            Err(error) => {
+                self.skipped.push(SkippedKeyring {
+                    keyring: keyring.clone(),
+                    path: parents.into(),
+                    error,
+                });
+                Ok(None)
+            },
+        }
+    }
+
+    fn visit(&mut self, item: KeyringChild, path: &[Keyring]) -> WalkAction {
+        let action = (self.visitor)(&WalkEntry {
+            item,
+            path,
+        });
+        if action == WalkAction::Stop {
+            self.stopped = true;
+        }
+        action
+    }
+
+    /// Visit the children of the last keyring in `path`.
+    ///
+    /// Each keyring which should be descended into is passed to `descend`.
+    fn visit_children<D>(&mut self, path: &[Keyring], mut descend: D) -> Result<()>
+    where
+        D: FnMut(&mut Self, Keyring) -> Result<()>,
+    {
+        let (keys, keyrings) = if let Some(children) = self.read(path)? {
+            children
+        } else {
+            return Ok(());
+        };
+
+        for key in keys {
+            if self.visit(KeyringChild::Key(key), path) == WalkAction::Stop {
+                return Ok(());
+            }
+        }
+
+        for keyring in keyrings {
+            // Keyrings may be linked into multiple keyrings; only visit them once.
+            if !self.visited.insert(keyring.serial()) {
+                continue;
+            }
+
+            match self.visit(KeyringChild::Keyring(keyring.clone()), path) {
+                WalkAction::Continue => descend(self, keyring)?,
+                WalkAction::Prune => (),
+                WalkAction::Stop => return Ok(()),
+            }
+
+            if self.stopped {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn depth_first(&mut self, path: &mut Vec<Keyring>) -> Result<()> {
+        let parent = path.clone();
+        self.visit_children(&parent, |walker, keyring| {
+            path.push(keyring);
+            let res = walker.depth_first(path);
+            path.pop();
+            res
+        })
+    }
+
+    fn breadth_first(&mut self, root: Keyring) -> Result<()> {
+        let mut queue = VecDeque::new();
+        queue.push_back(vec![root]);
+
+        while let Some(path) = queue.pop_front() {
+            self.visit_children(&path, |_, keyring| {
+                let mut child_path = path.clone();
+                child_path.push(keyring);
+                queue.push_back(child_path);
+                Ok(())
+            })?;
+
+            if self.stopped {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn walk<F>(root: &Keyring, order: WalkOrder, visitor: F) -> Result<Vec<SkippedKeyring>>
+where
+    F: FnMut(&WalkEntry) -> WalkAction,
+{
+    let mut walker = Walker {
+        visitor,
+        visited: Some(root.serial()).into_iter().collect(),
+        skipped: Vec::new(),
+        stopped: false,
+    };
+
+    match order {
+        WalkOrder::DepthFirst => walker.depth_first(&mut vec![root.clone()])?,
+        WalkOrder::BreadthFirst => walker.breadth_first(root.clone())?,
+    }
+
+    Ok(walker.skipped)
+}
@@ -0,0 +1,563 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::convert::TryInto;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+use keyutils_raw::*;
+use log::error;
+
+use crate::api::{Key, Keyring};
+use crate::error::{ErrnoContext, Error, Operation, Result};
+
+/// The default number of notifications which may be queued before notifications are lost.
+const DEFAULT_QUEUE_SIZE: libc::c_ulong = 256;
+/// The size of the buffer to read notifications into. Each notification is at most
+/// `WATCH_INFO_LENGTH` bytes long.
+const READ_BUFFER_SIZE: usize = 4096;
+/// The size of the common header for all notifications.
+const NOTIFICATION_HEADER_SIZE: usize = 8;
+/// The size of a key notification.
+const KEY_NOTIFICATION_SIZE: usize = 16;
+/// The size of a removal notification which includes the ID of the removed object.
+const REMOVAL_NOTIFICATION_SIZE: usize = 16;
+
+/// A change which happened to a watched key or keyring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// #[non_exhaustive]
+pub enum KeyEvent {
+    /// The key was instantiated.
+    Instantiated {
+        /// The error the key was rejected with, if any.
+        error: Option<errno::Errno>,
+    },
+    /// The payload of the key was updated.
+    Updated,
+    /// A key was linked into the watched keyring.
+    Linked {
+        /// The key which was linked.
+        key: KeyringSerial,
+    },
+    /// A key was unlinked from the watched keyring.
+    Unlinked {
+        /// The key which was unlinked.
+        key: KeyringSerial,
+    },
+    /// The watched keyring was cleared.
+    Cleared,
+    /// The key was revoked.
+    Revoked,
+    /// The key was invalidated.
+    Invalidated,
+    /// The attributes (ownership, permissions, or expiration) of the key were changed.
+    SetAttribute,
+    /// An event which could not be decoded.
+    ///
+    /// This is reported when the kernel sends a notification with data this crate does not
+    /// understand (e.g., a link notification without a key ID) so that it is not lost.
+    Raw {
+        /// The notification subtype.
+        subtype: u32,
+        /// The auxiliary data of the notification.
+        aux: u32,
+    },
+}
+
+/// A notification read from a `KeyWatcher`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// #[non_exhaustive]
+pub enum KeyNotification {
+    /// An event occurred on a watched key or keyring.
+    Key {
+        /// The ID the watch was registered with.
+        watch_id: u8,
+        /// The key or keyring the event happened to.
+        subject: KeyringSerial,
+        /// The event which occurred.
+        event: KeyEvent,
+    },
+    /// A watch was removed because the watched key was destroyed or the watch was removed.
+    WatchRemoved {
+        /// The ID the watch was registered with.
+        watch_id: u8,
+        /// The key or keyring which is no longer watched (if reported by the kernel).
+        subject: Option<KeyringSerial>,
+    },
+    /// Notifications were lost because the queue was full.
+    Lost,
+}
+
+/// A queue of notifications about changes to keys and keyrings.
+///
+/// Notifications are delivered through a notification pipe. Requires a kernel with
+/// `CONFIG_KEY_NOTIFICATIONS` enabled. The underlying file descriptor is available through
+/// `AsRawFd` so that it may be used with `poll(2)` and similar APIs.
+#[derive(Debug)]
+pub struct KeyWatcher {
+    read_fd: RawFd,
+    write_fd: RawFd,
+}
+
+impl KeyWatcher {
+    /// Create a new watcher with a default queue size.
+    pub fn new() -> Result<Self> {
+        Self::with_queue_size(DEFAULT_QUEUE_SIZE as usize)
+    }
+
+    /// Create a new watcher which may hold up to `size` pending notifications.
+    ///
+    /// The kernel limits the size of the queue to 512 notifications.
+    pub fn with_queue_size(size: usize) -> Result<Self> {
+        let mut fds = [0; 2];
+        let ret = unsafe { libc::pipe2(fds.as_mut_ptr(), O_NOTIFICATION_PIPE | libc::O_CLOEXEC) };
+        if ret < 0 {
+            return Err(Error::new(Operation::NotificationQueue, errno::errno()));
+        }
+        let watcher = KeyWatcher {
+            read_fd: fds[0],
+            write_fd: fds[1],
+        };
+
+        let size: libc::c_ulong = size
+            .try_into()
+            .map_err(|_| Error::new(Operation::NotificationQueue, errno::Errno(libc::EINVAL)))?;
+        let ret = unsafe { libc::ioctl(watcher.read_fd, IOC_WATCH_QUEUE_SET_SIZE as _, size) };
+        if ret < 0 {
+            return Err(Error::new(Operation::NotificationQueue, errno::errno()));
+        }
+
+        Ok(watcher)
+    }
+
+    fn watch_impl(&mut self, id: KeyringSerial, watch_id: Option<u8>) -> Result<()> {
+        keyctl_watch_key(id.into(), self.read_fd, watch_id).on(Operation::WatchKey, id)
+    }
+
+    /// Watch a key for changes.
+    ///
+    /// Notifications for the key will be tagged with `watch_id`. Requires the `view` permission
+    /// on the key.
+    pub fn watch_key(&mut self, key: &Key, watch_id: u8) -> Result<()> {
+        self.watch_impl(key.serial(), Some(watch_id))
+    }
+
+    /// Stop watching a key for changes.
+    pub fn unwatch_key(&mut self, key: &Key) -> Result<()> {
+        self.watch_impl(key.serial(), None)
+    }
+
+    /// Watch a keyring for changes.
+    ///
+    /// Notifications for the keyring will be tagged with `watch_id`. Requires the `view`
+    /// permission on the keyring.
+    pub fn watch_keyring(&mut self, keyring: &Keyring, watch_id: u8) -> Result<()> {
+        self.watch_impl(keyring.serial(), Some(watch_id))
+    }
+
+    /// Stop watching a keyring for changes.
+    pub fn unwatch_keyring(&mut self, keyring: &Keyring) -> Result<()> {
+        self.watch_impl(keyring.serial(), None)
+    }
+
+    /// Read pending notifications.
+    ///
+    /// Blocks until at least one notification is available.
+    pub fn read(&mut self) -> Result<Vec<KeyNotification>> {
+        let mut buffer = [0; READ_BUFFER_SIZE];
+        let sz = loop {
+            let ret = unsafe {
+                libc::read(
+                    self.read_fd,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                )
+            };
+            if ret >= 0 {
+                break ret as usize;
+            }
+            let err = errno::errno();
+            if err.0 != libc::EINTR {
+                return Err(Error::new(Operation::NotificationQueue, err));
+            }
+        };
+
+        parse_notifications(&buffer[..sz])
+    }
+}
+
+impl AsRawFd for KeyWatcher {
+    fn as_raw_fd(&self) -> RawFd {
+        self.read_fd
+    }
+}
+
+impl Drop for KeyWatcher {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.read_fd);
+            libc::close(self.write_fd);
+        }
+    }
+}
+
+fn read_u32(buffer: &[u8], offset: usize) -> u32 {
+    let bytes = buffer[offset..offset + 4]
+        .try_into()
+        .expect("the buffer length is checked before reading");
+    u32::from_ne_bytes(bytes)
+}
+
+fn read_u64(buffer: &[u8], offset: usize) -> u64 {
+    let bytes = buffer[offset..offset + 8]
+        .try_into()
+        .expect("the buffer length is checked before reading");
+    u64::from_ne_bytes(bytes)
+}
+
+// The header is a `type:24` and `subtype:8` bitfield pair; bitfields are laid out differently
+// depending on the endianness of the machine.
+#[cfg(target_endian = "little")]
+fn split_header(header: u32) -> (u32, u32) {
+    (header & 0x00ff_ffff, header >> 24)
+}
+
+#[cfg(target_endian = "big")]
+fn split_header(header: u32) -> (u32, u32) {
+    (header >> 8, header & 0xff)
+}
+
+fn key_event(subtype: u32, aux: u32) -> Option<KeyEvent> {
+    let affected_key = |make: fn(KeyringSerial) -> KeyEvent| {
+        if let Some(key) = KeyringSerial::new(aux as i32) {
+            make(key)
+        } else {
+            error!("A key link notification was received without a key ID");
+            KeyEvent::Raw {
+                subtype,
+                aux,
+            }
+        }
+    };
+
+    Some(match subtype {
+        NOTIFY_KEY_INSTANTIATED => {
+            KeyEvent::Instantiated {
+                error: match aux as i32 {
+                    0 => None,
+                    err => Some(errno::Errno(err.abs())),
+                },
+            }
+        },
+        NOTIFY_KEY_UPDATED => KeyEvent::Updated,
+        NOTIFY_KEY_LINKED => {
+            affected_key(|key| {
+                KeyEvent::Linked {
+                    key,
+                }
+            })
+        },
+        NOTIFY_KEY_UNLINKED => {
+            affected_key(|key| {
+                KeyEvent::Unlinked {
+                    key,
+                }
+            })
+        },
+        NOTIFY_KEY_CLEARED => KeyEvent::Cleared,
+        NOTIFY_KEY_REVOKED => KeyEvent::Revoked,
+        NOTIFY_KEY_INVALIDATED => KeyEvent::Invalidated,
+        NOTIFY_KEY_SETATTR => KeyEvent::SetAttribute,
+        _ => {
+            error!(
+                "New key notification detected! Please report this upstream to \
+                 https://github.com/mathstuf/rust-keyutils: {}",
+                subtype,
+            );
+            return None;
+        },
+    })
+}
+
+fn parse_notifications(mut buffer: &[u8]) -> Result<Vec<KeyNotification>> {
+    let mut notifications = Vec::new();
+
+    while !buffer.is_empty() {
+        if buffer.len() < NOTIFICATION_HEADER_SIZE {
+            error!("A truncated notification was read from the notification queue");
+            return Err(Error::new(
+                Operation::NotificationQueue,
+                errno::Errno(libc::EINVAL),
+            ));
+        }
+
+        let (type_, subtype) = split_header(read_u32(buffer, 0));
+        let info = read_u32(buffer, 4);
+        let len = (info & WATCH_INFO_LENGTH) as usize;
+        let watch_id = ((info & WATCH_INFO_ID) >> WATCH_INFO_ID__SHIFT) as u8;
+
+        if len < NOTIFICATION_HEADER_SIZE || buffer.len() < len {
+            error!(
+                "A notification with an invalid length was read from the notification queue: {}",
+                len,
+            );
+            return Err(Error::new(
+                Operation::NotificationQueue,
+                errno::Errno(libc::EINVAL),
+            ));
+        }
+        let (record, rest) = buffer.split_at(len);
+        buffer = rest;
+
+        let notification = match (type_, subtype) {
+            (WATCH_TYPE_KEY_NOTIFY, _) if len >= KEY_NOTIFICATION_SIZE => {
+                let subject = KeyringSerial::new(read_u32(record, 8) as i32);
+                let event = key_event(subtype, read_u32(record, 12));
+                subject.and_then(|subject| {
+                    event.map(|event| {
+                        KeyNotification::Key {
+                            watch_id,
+                            subject,
+                            event,
+                        }
+                    })
+                })
+            },
+            (WATCH_TYPE_META, WATCH_META_REMOVAL_NOTIFICATION) => {
+                let subject = if len >= REMOVAL_NOTIFICATION_SIZE {
+                    read_u64(record, 8)
+                        .try_into()
+                        .ok()
+                        .and_then(KeyringSerial::new)
+                } else {
+                    None
+                };
+                Some(KeyNotification::WatchRemoved {
+                    watch_id,
+                    subject,
+                })
+            },
+            (WATCH_TYPE_META, WATCH_META_LOSS_NOTIFICATION) => Some(KeyNotification::Lost),
+            // Ignore notifications for other subsystems.
+            _ => None,
+        };
+
+        notifications.extend(notification);
+    }
+
+    Ok(notifications)
+}
+
+#[cfg(test)]
+mod tests {
+    use keyutils_raw::*;
+
+    use super::{parse_notifications, KeyEvent, KeyNotification};
+    use crate::KeyringSerial;
+
+    fn header(type_: u32, subtype: u32, watch_id: u8, len: usize) -> Vec<u8> {
+        #[cfg(target_endian = "little")]
+        let header = type_ | (subtype << 24);
+        #[cfg(target_endian = "big")]
+        let header = (type_ << 8) | subtype;
+        let info = ((watch_id as u32) << WATCH_INFO_ID__SHIFT) | len as u32;
+
+        let mut buffer = Vec::new();
+        buffer.extend(header.to_ne_bytes().iter());
+        buffer.extend(info.to_ne_bytes().iter());
+        buffer
+    }
+
+    fn key_notification(subtype: u32, watch_id: u8, key_id: i32, aux: i32) -> Vec<u8> {
+        let mut buffer = header(WATCH_TYPE_KEY_NOTIFY, subtype, watch_id, 16);
+        buffer.extend(key_id.to_ne_bytes().iter());
+        buffer.extend(aux.to_ne_bytes().iter());
+        buffer
+    }
+
+    fn serial(id: i32) -> KeyringSerial {
+        KeyringSerial::new(id).unwrap()
+    }
+
+    #[test]
+    fn test_parse_key_notifications() {
+        let mut buffer = Vec::new();
+        buffer.extend(key_notification(NOTIFY_KEY_INSTANTIATED, 1, 10, 0));
+        buffer.extend(key_notification(
+            NOTIFY_KEY_INSTANTIATED,
+            1,
+            10,
+            -libc::EKEYREJECTED,
+        ));
+        buffer.extend(key_notification(NOTIFY_KEY_UPDATED, 1, 10, 0));
+        buffer.extend(key_notification(NOTIFY_KEY_LINKED, 2, 20, 10));
+        buffer.extend(key_notification(NOTIFY_KEY_UNLINKED, 2, 20, 10));
+        buffer.extend(key_notification(NOTIFY_KEY_CLEARED, 2, 20, 0));
+        buffer.extend(key_notification(NOTIFY_KEY_REVOKED, 1, 10, 0));
+        buffer.extend(key_notification(NOTIFY_KEY_INVALIDATED, 1, 10, 0));
+        buffer.extend(key_notification(NOTIFY_KEY_SETATTR, 1, 10, 0));
+
+        let key_event = |watch_id, id, event| {
+            KeyNotification::Key {
+                watch_id,
+                subject: serial(id),
+                event,
+            }
+        };
+        assert_eq!(
+            parse_notifications(&buffer).unwrap(),
+            [
+                key_event(
+                    1,
+                    10,
+                    KeyEvent::Instantiated {
+                        error: None,
+                    },
+                ),
+                key_event(
+                    1,
+                    10,
+                    KeyEvent::Instantiated {
+                        error: Some(errno::Errno(libc::EKEYREJECTED)),
+                    },
+                ),
+                key_event(1, 10, KeyEvent::Updated),
+                key_event(
+                    2,
+                    20,
+                    KeyEvent::Linked {
+                        key: serial(10),
+                    },
+                ),
+                key_event(
+                    2,
+                    20,
+                    KeyEvent::Unlinked {
+                        key: serial(10),
+                    },
+                ),
+                key_event(2, 20, KeyEvent::Cleared),
+                key_event(1, 10, KeyEvent::Revoked),
+                key_event(1, 10, KeyEvent::Invalidated),
+                key_event(1, 10, KeyEvent::SetAttribute),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_meta_notifications() {
+        let mut buffer = Vec::new();
+        buffer.extend(header(
+            WATCH_TYPE_META,
+            WATCH_META_REMOVAL_NOTIFICATION,
+            3,
+            16,
+        ));
+        buffer.extend(30_u64.to_ne_bytes().iter());
+        buffer.extend(header(
+            WATCH_TYPE_META,
+            WATCH_META_REMOVAL_NOTIFICATION,
+            4,
+            8,
+        ));
+        buffer.extend(header(WATCH_TYPE_META, WATCH_META_LOSS_NOTIFICATION, 0, 8));
+
+        assert_eq!(
+            parse_notifications(&buffer).unwrap(),
+            [
+                KeyNotification::WatchRemoved {
+                    watch_id: 3,
+                    subject: Some(serial(30)),
+                },
+                KeyNotification::WatchRemoved {
+                    watch_id: 4,
+                    subject: None,
+                },
+                KeyNotification::Lost,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_skips_unknown_notifications() {
+        let mut buffer = Vec::new();
+        buffer.extend(header(0x100, 0, 1, 12));
+        buffer.extend([0; 4].iter());
+        buffer.extend(key_notification(0xff, 1, 10, 0));
+        buffer.extend(key_notification(NOTIFY_KEY_UPDATED, 1, 10, 0));
+
+        assert_eq!(
+            parse_notifications(&buffer).unwrap(),
+            [KeyNotification::Key {
+                watch_id: 1,
+                subject: serial(10),
+                event: KeyEvent::Updated,
+            }],
+        );
+    }
+
+    #[test]
+    fn test_parse_link_without_key() {
+        let mut buffer = Vec::new();
+        buffer.extend(key_notification(NOTIFY_KEY_LINKED, 2, 20, 0));
+        buffer.extend(key_notification(NOTIFY_KEY_UNLINKED, 2, 20, 0));
+
+        assert_eq!(
+            parse_notifications(&buffer).unwrap(),
+            [
+                KeyNotification::Key {
+                    watch_id: 2,
+                    subject: serial(20),
+                    event: KeyEvent::Raw {
+                        subtype: NOTIFY_KEY_LINKED,
+                        aux: 0,
+                    },
+                },
+                KeyNotification::Key {
+                    watch_id: 2,
+                    subject: serial(20),
+                    event: KeyEvent::Raw {
+                        subtype: NOTIFY_KEY_UNLINKED,
+                        aux: 0,
+                    },
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_truncated_notifications() {
+        let buffer = key_notification(NOTIFY_KEY_UPDATED, 1, 10, 0);
+
+        let err = parse_notifications(&buffer[..4]).unwrap_err();
+        assert_eq!(err, errno::Errno(libc::EINVAL));
+
+        let err = parse_notifications(&buffer[..12]).unwrap_err();
+        assert_eq!(err, errno::Errno(libc::EINVAL));
+
+        let err = parse_notifications(&header(WATCH_TYPE_META, 0, 0, 4)).unwrap_err();
+        assert_eq!(err, errno::Errno(libc::EINVAL));
+    }
+}